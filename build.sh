@@ -2,4 +2,5 @@
 
 cargo build --release
 cp target/release/slidingpuzzle out
+cp target/release/solve_boards out
 cp -r resources out/
@@ -0,0 +1,48 @@
+// Solves the first ten of Korf's 100 random 15-puzzle instances and checks the
+// results against their known optimal lengths.
+//
+// cargo run --release --example solver_benchmark
+
+use std::time::Instant;
+
+use slidingpuzzle::game::puzzle::tiles::{Board, Solver};
+
+// Blank is 0 and belongs in the top left corner
+const KORF_INSTANCES: [([usize; 16], usize); 10] = [
+    ([14, 13, 15, 7, 11, 12, 9, 5, 6, 0, 2, 1, 4, 8, 10, 3], 57),
+    ([13, 5, 4, 10, 9, 12, 8, 14, 2, 3, 7, 1, 0, 15, 11, 6], 55),
+    ([14, 7, 8, 2, 13, 11, 10, 4, 9, 12, 5, 0, 3, 6, 1, 15], 59),
+    ([5, 12, 10, 7, 15, 11, 14, 0, 8, 2, 1, 13, 3, 4, 9, 6], 56),
+    ([4, 7, 14, 13, 10, 3, 9, 12, 11, 5, 6, 15, 1, 2, 8, 0], 56),
+    ([14, 7, 1, 9, 12, 3, 6, 15, 8, 11, 2, 5, 10, 0, 4, 13], 52),
+    ([2, 11, 15, 5, 13, 4, 6, 7, 12, 8, 10, 1, 9, 3, 14, 0], 52),
+    ([12, 11, 15, 3, 8, 0, 4, 2, 6, 13, 9, 5, 14, 1, 10, 7], 50),
+    ([3, 14, 9, 11, 5, 4, 8, 2, 13, 12, 6, 7, 10, 1, 15, 0], 46),
+    ([13, 11, 8, 9, 0, 15, 7, 10, 4, 3, 6, 14, 5, 12, 2, 1], 59),
+];
+
+fn main() {
+    let solver = Solver::default();
+    let total = Instant::now();
+    let mut total_nodes = 0;
+
+    println!("{:>3} {:>7} {:>6} {:>12} {:>10}", "#", "optimal", "found", "nodes", "seconds");
+    for (num, (cells, optimal_len)) in KORF_INSTANCES.iter().enumerate() {
+        let board = Board::from_cells(4, 4, cells.to_vec(), 0).expect("Invalid benchmark board");
+
+        let start = Instant::now();
+        let solution = solver.solve_optimal(&board).expect("Failed to solve benchmark board");
+        let elapsed = start.elapsed();
+
+        let mut check = board.clone();
+        for &mv in &solution.moves {
+            check.move_tile(mv);
+        }
+        assert!(check.is_solved(), "Solution for #{} doesn't solve the board", num + 1);
+        assert_eq!(solution.moves.len(), *optimal_len, "Solution for #{} isn't optimal", num + 1);
+
+        total_nodes += solution.nodes;
+        println!("{:>3} {:>7} {:>6} {:>12} {:>10.3}", num + 1, optimal_len, solution.moves.len(), solution.nodes, elapsed.as_secs_f64());
+    }
+    println!("total: {} nodes in {:.3}s", total_nodes, total.elapsed().as_secs_f64());
+}
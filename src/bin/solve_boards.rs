@@ -0,0 +1,177 @@
+// Solves boards without opening a window, for testing the solver and seeing
+// how hard scrambles are. Reads one board per line in the usual notation,
+// e.g. "1 2 3/4 0 5/7 8 6". Blank lines and lines starting with # are skipped.
+//
+//   slidingpuzzle scramble --count 100 | solve_boards --format csv > results.csv
+
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::PathBuf,
+    process::exit,
+    time::Instant,
+};
+
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+use slidingpuzzle::game::puzzle::tiles::{format_moves, Board, Solver};
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "solve_boards", about = "Solve sliding puzzle boards read from a file or stdin")]
+struct Args {
+    #[arg(help = "Read boards from this file instead of stdin", value_name = "FILE")]
+    input: Option<PathBuf>,
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    #[arg(long, help = "Give up on an optimal solution after looking at this many boards")]
+    node_limit: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+struct BoardResult {
+    // Line number in the input, counting from 1
+    line: usize,
+    board: String,
+    // Everything below is empty if the board couldn't be solved
+    length: Option<usize>,
+    optimal: Option<bool>,
+    nodes: Option<u64>,
+    millis: Option<f64>,
+    moves: Option<String>,
+    error: Option<String>,
+}
+
+fn solve_line(solver: &Solver, line: usize, notation: &str) -> BoardResult {
+    let mut result =
+        BoardResult { line, board: notation.to_string(), length: None, optimal: None, nodes: None, millis: None, moves: None, error: None };
+    let board: Board = match notation.parse() {
+        Ok(board) => board,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+    // Written out the same way no matter how it was typed
+    result.board = board.to_string();
+
+    let start = Instant::now();
+    match solver.solve(&board) {
+        Some(solution) => {
+            result.millis = Some(start.elapsed().as_secs_f64() * 1000.0);
+            result.length = Some(solution.moves.len());
+            result.optimal = Some(solution.optimal);
+            result.nodes = Some(solution.nodes);
+            result.moves = Some(format_moves(&solution.moves));
+        }
+        None => result.error = Some("The board can't be solved".to_string()),
+    }
+    result
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn opt_to_string<T: ToString>(value: &Option<T>) -> String { value.as_ref().map_or(String::new(), |value| value.to_string()) }
+
+fn print_text(results: &[BoardResult]) {
+    for result in results {
+        match (&result.error, result.length) {
+            (Some(error), _) => println!("{}: {} - {}", result.line, result.board, error),
+            (None, Some(length)) => println!(
+                "{}: {} - {} moves{}, {} nodes, {:.2} ms",
+                result.line,
+                result.board,
+                length,
+                if result.optimal == Some(true) { " (optimal)" } else { "" },
+                opt_to_string(&result.nodes),
+                result.millis.unwrap_or_default()
+            ),
+            (None, None) => {}
+        }
+    }
+
+    let solved: Vec<&BoardResult> = results.iter().filter(|result| result.length.is_some()).collect();
+    if solved.is_empty() {
+        return;
+    }
+    let total_length: usize = solved.iter().filter_map(|result| result.length).sum();
+    let total_nodes: u64 = solved.iter().filter_map(|result| result.nodes).sum();
+    let total_millis: f64 = solved.iter().filter_map(|result| result.millis).sum();
+    println!(
+        "Solved {} of {}: {:.2} moves on average, {} nodes, {:.2} ms total",
+        solved.len(),
+        results.len(),
+        total_length as f64 / solved.len() as f64,
+        total_nodes,
+        total_millis
+    );
+}
+
+fn print_csv(results: &[BoardResult]) {
+    println!("line,board,length,optimal,nodes,millis,moves,error");
+    for result in results {
+        println!(
+            "{},{},{},{},{},{},{},{}",
+            result.line,
+            csv_field(&result.board),
+            opt_to_string(&result.length),
+            opt_to_string(&result.optimal),
+            opt_to_string(&result.nodes),
+            result.millis.map_or(String::new(), |millis| format!("{:.3}", millis)),
+            opt_to_string(&result.moves),
+            csv_field(&opt_to_string(&result.error))
+        );
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let reader: Box<dyn BufRead> = match &args.input {
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(e) => {
+                eprintln!("Couldn't open {}: {}", path.display(), e);
+                exit(1);
+            }
+        },
+        None => Box::new(BufReader::new(io::stdin())),
+    };
+
+    let solver = Solver::new(args.node_limit);
+    let mut results = vec![];
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("Couldn't read line {}: {}", idx + 1, e);
+            exit(1);
+        });
+        let notation = line.trim();
+        if notation.is_empty() || notation.starts_with('#') {
+            continue;
+        }
+        results.push(solve_line(&solver, idx + 1, notation));
+    }
+
+    match args.format {
+        Format::Text => print_text(&results),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&results).expect("Failed to write JSON")),
+        Format::Csv => print_csv(&results),
+    }
+
+    // So scripts notice bad boards
+    if results.iter().any(|result| result.error.is_some()) {
+        exit(2);
+    }
+}
@@ -0,0 +1,224 @@
+// Command line options. Without a subcommand the game window opens,
+// otherwise the subcommand runs without one.
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use slidingpuzzle::game::{
+    player::{profile_file, save_file, settings_scene::parse_board_size, Player},
+    puzzle::tiles::{format_moves, tile_random::TileRandom, Board, NotationError, Scramble, Solver},
+    resources::{image_loader::PuzzleId, StartGame},
+    ui::error_message,
+};
+
+#[derive(Parser, Debug)]
+#[command(name = "slidingpuzzle", about = "A sliding puzzle game")]
+pub struct Cli {
+    #[arg(long, help = "Window width", default_value_t = 1820.0)]
+    pub width: f32,
+    #[arg(long, help = "Window height", default_value_t = 1030.0)]
+    pub height: f32,
+    #[arg(long, help = "Fill the screen, the height is kept")]
+    pub fullscreen: bool,
+
+    #[arg(long, help = "Used instead of the resources next to the executable", value_name = "DIR")]
+    pub resources: Option<PathBuf>,
+    #[arg(long, help = "Where the profile and replays are saved", value_name = "DIR")]
+    pub save_dir: Option<PathBuf>,
+    #[arg(long, help = "The username of the profile for stats and export, otherwise the last one played", value_name = "NAME")]
+    pub profile: Option<String>,
+    #[arg(long, help = "A theme.json to use instead of the one in the resources", value_name = "FILE")]
+    pub theme: Option<PathBuf>,
+    #[arg(long, help = "error, warn, info, debug or trace. RUST_LOG takes precedence.", default_value = "error")]
+    pub log_level: String,
+
+    #[arg(long, help = "Start straight into this puzzle, its image's file name without the extension", value_name = "ID")]
+    pub puzzle: Option<String>,
+    #[arg(long, help = "Grid size for --puzzle, e.g. 4 or 3x5", value_name = "SIZE", requires = "puzzle", conflicts_with = "board", value_parser = parse_grid)]
+    pub grid: Option<(usize, usize)>,
+    #[arg(long, help = "Start from this board instead of a scramble, e.g. \"1,2,3/4,0,5/7,8,6\"", value_name = "BOARD", value_parser = parse_solvable_board)]
+    pub board: Option<Board>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[command(about = "Print a solution for a board")]
+    Solve {
+        #[arg(help = "e.g. \"1,2,3/4,0,5/7,8,6\"", value_parser = parse_solvable_board)]
+        board: Board,
+        #[arg(long, help = "Give up on an optimal solution after looking at this many boards")]
+        node_limit: Option<u64>,
+    },
+    #[command(about = "Print scrambled boards, one per line")]
+    Scramble {
+        #[arg(long, help = "e.g. 4 or 3x5", default_value = "4", value_parser = parse_grid)]
+        grid: (usize, usize),
+        #[arg(long, help = "How many boards to print", default_value_t = 1)]
+        count: usize,
+        #[arg(long, help = "Only boards that take at least this many moves")]
+        min_moves: Option<u32>,
+        #[arg(long, help = "The same seed always gives the same boards")]
+        seed: Option<u64>,
+    },
+    #[command(about = "Print a profile's statistics")]
+    Stats,
+    #[command(about = "Write a profile to a JSON or TOML file")]
+    Export {
+        #[arg(help = "Ends in .json or .toml")]
+        file: PathBuf,
+    },
+    #[command(about = "Add a profile from a JSON or TOML file, replacing it if it's already here")]
+    Import {
+        #[arg(help = "Ends in .json or .toml")]
+        file: PathBuf,
+    },
+}
+
+impl Cli {
+    pub fn start_game(&self) -> Option<StartGame> {
+        let puzzle_id = self.puzzle.clone().map(PuzzleId);
+        match (&self.board, puzzle_id) {
+            (Some(board), puzzle_id) => Some(StartGame::Custom(puzzle_id, board.clone())),
+            (None, Some(puzzle_id)) => Some(StartGame::Puzzle(puzzle_id, self.grid)),
+            (None, None) => None,
+        }
+    }
+}
+
+fn parse_grid(value: &str) -> Result<(usize, usize), String> {
+    parse_board_size(value).ok_or_else(|| "expected a size like 4 or 3x5, at least 2 on each side".to_string())
+}
+
+fn parse_solvable_board(value: &str) -> Result<Board, String> {
+    let board: Board = value.parse().map_err(|e: NotationError| e.to_string())?;
+    if !board.is_solvable() {
+        return Err("the board can't be solved".to_string());
+    }
+    Ok(board)
+}
+
+// Returns the exit code
+pub fn run_command(command: Command, profile: Option<&str>) -> i32 {
+    match command {
+        Command::Solve { board, node_limit } => {
+            let Some(solution) = Solver::new(node_limit).solve(&board) else {
+                eprintln!("The board can't be solved");
+                return 1;
+            };
+            println!("{}", format_moves(&solution.moves));
+            println!("{} moves{}", solution.moves.len(), if solution.optimal { " (optimal)" } else { "" });
+        }
+        Command::Scramble { grid: (rows, cols), count, min_moves, seed } => {
+            let mut rng = match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            let scramble = min_moves.map_or(Scramble::Random, Scramble::MinMoves);
+            let mut short = 0;
+            for _ in 0..count {
+                // Same as a new game, the blank belongs in a random cell
+                let mut board = Board::new(rows, cols, (rng.gen_range(0..rows), rng.gen_range(0..cols)));
+                if TileRandom::scramble(&mut board, scramble, &mut rng) < min_moves.unwrap_or(0) {
+                    short += 1;
+                }
+                println!("{}", board);
+            }
+            if short > 0 {
+                eprintln!(
+                    "{} of the boards take fewer than {} moves, a {}x{} board can't take more than {}",
+                    short,
+                    min_moves.unwrap_or(0),
+                    rows,
+                    cols,
+                    TileRandom::max_min_moves(rows, cols)
+                );
+            }
+        }
+        Command::Stats => {
+            let Some(player) = load_player(profile) else { return 1 };
+            println!("Player: {}", player.username());
+            for (puzzle_id, stats) in player.completed_puzzles.iter() {
+                for stat in stats {
+                    println!(
+                        "{} {} {}{}: {} moves ({} multi-tile), {} hints, {}:{:02}, finished {}{}",
+                        puzzle_id,
+                        stat.grid_name(),
+                        stat.mode,
+                        stat.opponent.as_ref().map_or(String::new(), |opponent| format!(" against {}", opponent)),
+                        stat.move_count,
+                        stat.multi_tile_move_count,
+                        stat.hints_used,
+                        stat.duration.as_secs() / 60,
+                        stat.duration.as_secs() % 60,
+                        stat.finish_time.format("%Y-%m-%d %H:%M"),
+                        stat.scramble_seed.map_or(String::new(), |seed| format!(", seed {}", seed))
+                    );
+                }
+            }
+            for (puzzle_id, attempts) in player.attempts.iter() {
+                let finished = player.completed_puzzles.get(puzzle_id).map_or(0, Vec::len);
+                println!("{}: finished {} of {} games started", puzzle_id, finished, attempts);
+            }
+        }
+        Command::Export { file } => {
+            let Some(player) = load_player(profile) else { return 1 };
+            if let Err(e) = profile_file::export(&player, &file) {
+                eprintln!("{}", error_message(&e));
+                return 1;
+            }
+            println!("Exported {}'s profile to {}", player.username(), file.display());
+        }
+        Command::Import { file } => {
+            // Checking the username against the other profiles needs them moved first
+            migrate_single_profile();
+            let player = match profile_file::import(&file) {
+                Ok(player) => player,
+                Err(e) => {
+                    eprintln!("{}", error_message(&e));
+                    return 1;
+                }
+            };
+            if let Err(e) = player.save() {
+                eprintln!("Couldn't save the profile: {}", error_message(&e));
+                return 1;
+            }
+            let games: usize = player.completed_puzzles.values().map(Vec::len).sum();
+            println!("Imported {}'s profile with {} games", player.username(), games);
+            let backup = save_file::backup_path(&save_file::profile_path(player.id()));
+            if backup.is_file() {
+                println!("The profile it replaced is in {}", backup.display());
+            }
+        }
+    }
+    0
+}
+
+// Like the game does as it starts, so the profiles are all in one place
+fn migrate_single_profile() {
+    if let Err(e) = Player::migrate_single_profile() {
+        eprintln!("Couldn't move player.dat to the profiles: {}", error_message(&e));
+    }
+}
+
+fn load_player(profile: Option<&str>) -> Option<Player> {
+    for bad_save in Player::quarantine_bad_saves() {
+        eprintln!("{}", bad_save.reason);
+        match bad_save.quarantined {
+            Some(path) => eprintln!("It was moved to {}", path.display()),
+            None => eprintln!("It couldn't be moved out of the way"),
+        }
+        if bad_save.backup.is_some() {
+            let backup = save_file::backup_path(&bad_save.path);
+            eprintln!("The save before it is {}, copy it to {} to restore it", backup.display(), bad_save.path.display());
+        }
+    }
+    migrate_single_profile();
+    profile
+        .map_or_else(Player::load, Player::load_by_username)
+        .map_err(|e| eprintln!("Couldn't load the profile: {}", error_message(&e)))
+        .ok()
+}
@@ -33,18 +33,15 @@ impl<T: Tweenable> Animation<T> {
 
         let mut modified_states = vec![];
         for i in animations.iter() {
-            match i {
-                AnimationData::Sequence(e) => {
-                    for x in modified_states.iter() {
-                        if Rc::ptr_eq(&e.0, x) {
-                            continue;
-                        }
+            if let AnimationData::Sequence(e) = i {
+                for x in modified_states.iter() {
+                    if Rc::ptr_eq(&e.0, x) {
+                        continue;
                     }
-
-                    e.0.borrow_mut().set_state(e.1.now());
-                    modified_states.push(e.0.clone())
                 }
-                _ => {}
+
+                e.0.borrow_mut().set_state(e.1.now());
+                modified_states.push(e.0.clone())
             }
         }
 
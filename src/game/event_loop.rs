@@ -0,0 +1,175 @@
+// A copy of event::run from ggez 0.8.1 (src/event.rs) that also passes files
+// dropped onto the window on to the game, since EventHandler has no callback
+// for them. Everything else is handled the way ggez does, check it against
+// ggez's again when upgrading.
+//
+// GamepadId can't be made outside of ggez, so gamepad presses and sticks go
+// to GameState's own methods instead of EventHandler's. Releases aren't used.
+
+use ggez::{
+    event::{
+        winit_event::{ElementState, Event, KeyboardInput as WinitKeyboardInput, MouseScrollDelta, WindowEvent},
+        ControlFlow, ErrorOrigin, EventHandler, EventLoop,
+    },
+    input::{
+        gamepad::gilrs,
+        keyboard::{KeyInput, KeyMods},
+    },
+    winit::dpi::LogicalPosition,
+    Context, GameResult,
+};
+
+use super::GameState;
+
+// Like ggez's catch_error, true if the game should stop
+fn check(ctx: &mut Context, result: GameResult, state: &mut GameState, control_flow: &mut ControlFlow, origin: ErrorOrigin) -> bool {
+    if let Err(e) = result {
+        log::error!("Error on EventHandler {:?}: {:?}", origin, e);
+        if state.on_error(ctx, origin, e) {
+            *control_flow = ControlFlow::Exit;
+            return true;
+        }
+    }
+    false
+}
+
+// For the frame itself, which ggez doesn't let the game handle
+fn check_frame(result: GameResult, what: &str, control_flow: &mut ControlFlow) {
+    if let Err(e) = result {
+        log::error!("Error on GraphicsContext::{}(): {:?}", what, e);
+        *control_flow = ControlFlow::Exit;
+    }
+}
+
+// quit_event returning false lets the game close
+fn quit(ctx: &mut Context, state: &mut GameState, control_flow: &mut ControlFlow) -> bool {
+    match state.quit_event(ctx) {
+        Ok(false) => {
+            ctx.continuing = false;
+            false
+        }
+        res => check(ctx, res.map(|_| ()), state, control_flow, ErrorOrigin::QuitEvent),
+    }
+}
+
+pub fn run(mut ctx: Context, event_loop: EventLoop<()>, mut state: GameState) -> ! {
+    event_loop.run(move |mut event, _, control_flow| {
+        let ctx = &mut ctx;
+        let state = &mut state;
+
+        if ctx.quit_requested {
+            ctx.quit_requested = false;
+            if quit(ctx, state, control_flow) {
+                return;
+            }
+        }
+        if !ctx.continuing {
+            *control_flow = ControlFlow::Exit;
+            return;
+        }
+        *control_flow = ControlFlow::Poll;
+
+        ggez::event::process_event(ctx, &mut event);
+        match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::Resized(size) => {
+                    let res = state.resize_event(ctx, size.width as f32, size.height as f32);
+                    check(ctx, res, state, control_flow, ErrorOrigin::ResizeEvent);
+                }
+                WindowEvent::CloseRequested => {
+                    quit(ctx, state, control_flow);
+                }
+                WindowEvent::Focused(gained) => {
+                    let res = state.focus_event(ctx, gained);
+                    check(ctx, res, state, control_flow, ErrorOrigin::FocusEvent);
+                }
+                WindowEvent::DroppedFile(path) => state.file_dropped_event(ctx, path),
+                WindowEvent::ReceivedCharacter(c) => {
+                    let res = state.text_input_event(ctx, c);
+                    check(ctx, res, state, control_flow, ErrorOrigin::TextInputEvent);
+                }
+                WindowEvent::ModifiersChanged(mods) => ctx.keyboard.set_modifiers(KeyMods::from(mods)),
+                WindowEvent::KeyboardInput {
+                    input: WinitKeyboardInput { state: element_state, virtual_keycode: keycode, scancode, .. },
+                    ..
+                } => {
+                    let key_input = KeyInput { scancode, keycode, mods: ctx.keyboard.active_mods() };
+                    match element_state {
+                        ElementState::Pressed => {
+                            let repeat = ctx.keyboard.is_key_repeated();
+                            let res = state.key_down_event(ctx, key_input, repeat);
+                            check(ctx, res, state, control_flow, ErrorOrigin::KeyDownEvent);
+                        }
+                        ElementState::Released => {
+                            let res = state.key_up_event(ctx, key_input);
+                            check(ctx, res, state, control_flow, ErrorOrigin::KeyUpEvent);
+                        }
+                    }
+                }
+                WindowEvent::MouseWheel { delta, .. } => {
+                    let (x, y) = match delta {
+                        MouseScrollDelta::LineDelta(x, y) => (x, y),
+                        MouseScrollDelta::PixelDelta(pos) => {
+                            let LogicalPosition { x, y } = pos.to_logical::<f32>(ctx.gfx.window().scale_factor());
+                            (x, y)
+                        }
+                    };
+                    let res = state.mouse_wheel_event(ctx, x, y);
+                    check(ctx, res, state, control_flow, ErrorOrigin::MouseWheelEvent);
+                }
+                WindowEvent::MouseInput { state: element_state, button, .. } => {
+                    let position = ctx.mouse.position();
+                    match element_state {
+                        ElementState::Pressed => {
+                            let res = state.mouse_button_down_event(ctx, button, position.x, position.y);
+                            check(ctx, res, state, control_flow, ErrorOrigin::MouseButtonDownEvent);
+                        }
+                        ElementState::Released => {
+                            let res = state.mouse_button_up_event(ctx, button, position.x, position.y);
+                            check(ctx, res, state, control_flow, ErrorOrigin::MouseButtonUpEvent);
+                        }
+                    }
+                }
+                WindowEvent::CursorMoved { .. } => {
+                    let (position, delta) = (ctx.mouse.position(), ctx.mouse.last_delta());
+                    let res = state.mouse_motion_event(ctx, position.x, position.y, delta.x, delta.y);
+                    check(ctx, res, state, control_flow, ErrorOrigin::MouseMotionEvent);
+                }
+                WindowEvent::Touch(touch) => {
+                    let res = state.touch_event(ctx, touch.phase, touch.location.x, touch.location.y);
+                    check(ctx, res, state, control_flow, ErrorOrigin::TouchEvent);
+                }
+                _ => {}
+            },
+            Event::MainEventsCleared => {
+                ctx.time.tick();
+
+                while let Some(gilrs::Event { event, .. }) = ctx.gamepad.next_event() {
+                    match event {
+                        gilrs::EventType::ButtonPressed(button, _) => state.gamepad_button(ctx, button),
+                        gilrs::EventType::AxisChanged(axis, value, _) => state.gamepad_axis(ctx, axis, value),
+                        _ => {}
+                    }
+                }
+
+                let res = state.update(ctx);
+                if check(ctx, res, state, control_flow, ErrorOrigin::Update) {
+                    return;
+                }
+                check_frame(ctx.gfx.begin_frame(), "begin_frame", control_flow);
+                let res = state.draw(ctx);
+                if check(ctx, res, state, control_flow, ErrorOrigin::Draw) {
+                    return;
+                }
+                check_frame(ctx.gfx.end_frame(), "end_frame", control_flow);
+
+                // The mouse delta adds up over a frame, and the keyboard and
+                // mouse state is compared against the last frame's
+                ctx.mouse.reset_delta();
+                ctx.keyboard.save_keyboard_state();
+                ctx.mouse.save_mouse_state();
+            }
+            _ => {}
+        }
+    })
+}
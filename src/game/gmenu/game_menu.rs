@@ -1,13 +1,21 @@
-use crate::game::input::InputAction;
+use std::path::PathBuf;
+
+use crate::game::input::{mouse::MouseAction, InputAction};
+use crate::game::puzzle::import_scene::ImportScene;
 use crate::game::resources::theme::Theme;
-use crate::game::{animation::DrawablePos, ui::uitext::UIText};
+use crate::game::{
+    animation::DrawablePos,
+    ui::{
+        error_scene::{main_menu, try_scene},
+        uitext::UIText,
+    },
+};
 
 use super::menu_item_list::{GameMenuItemList, NewGameMenuItemData};
 
 use crate::game::{drawable::Drawable as SlidingPuzzleDrawable, scene::Scene};
 use ggez::graphics::Canvas;
 
-
 use ggez::{Context, GameResult};
 
 pub trait GameMenuData {
@@ -19,6 +27,8 @@ pub trait GameMenuData {
 pub struct GameMenu {
     menu_mappings: GameMenuItemList,
     title_text: UIText,
+    // Dragged onto the window, to be imported
+    dropped_file: Option<PathBuf>,
 }
 
 impl GameMenu {
@@ -26,7 +36,11 @@ impl GameMenu {
         let title_text = UIText::new(T::title(), Theme::fg_color(), 78.0, DrawablePos { x: 90.0, y: 90.0 });
 
         let tx_s = title_text.text.measure(ctx)?;
-        Ok(Self { menu_mappings: GameMenuItemList::new(ctx, T::menu_mappings(), 90.0, tx_s.y + 110.0, tx_s.x, 80.0)?, title_text })
+        Ok(Self {
+            menu_mappings: GameMenuItemList::new(ctx, T::menu_mappings(), 90.0, tx_s.y + 110.0, tx_s.x, 80.0)?,
+            title_text,
+            dropped_file: None,
+        })
     }
 }
 
@@ -42,5 +56,16 @@ impl SlidingPuzzleDrawable for GameMenu {
 impl Scene for GameMenu {
     fn handle_input_event(&mut self, ctx: &mut Context, key_input: InputAction) { self.menu_mappings.handle_input_event(ctx, key_input); }
 
-    fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> { self.menu_mappings.next_scene(ctx) }
+    fn handle_mouse_event(&mut self, ctx: &mut Context, mouse_input: MouseAction) {
+        self.menu_mappings.handle_mouse_event(ctx, mouse_input);
+    }
+
+    fn file_dropped_event(&mut self, _ctx: &mut Context, path: PathBuf) { self.dropped_file = Some(path); }
+
+    fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
+        if let Some(path) = self.dropped_file.take() {
+            return Some(try_scene(ctx, "Couldn't Open the Import", move |ctx| ImportScene::new(ctx, Some(path.clone())), main_menu));
+        }
+        self.menu_mappings.next_scene(ctx)
+    }
 }
@@ -0,0 +1,65 @@
+use std::cmp::Reverse;
+
+use ggez::{Context, GameResult};
+
+use crate::game::{
+    player::{format_duration, stats_scene::StatsScene, PLAYER},
+    puzzle::{replay::Replay, replay_scene::ReplayScene},
+    resources::image_loader::ImageLoader,
+    scene::Scene,
+    ui::error_scene::{back_to_main_menu, try_scene},
+};
+
+use super::{
+    game_menu::{GameMenu, GameMenuData},
+    menu_item_list::{NewGameMenuItemData, NewGameMenuItemDataVariant},
+};
+
+// Any more won't fit on the screen
+const MAX_LISTED_GAMES: usize = 5;
+
+// Where the scenes opened from the history go back to when they can't be made
+pub fn history(context: &mut Context) -> GameResult<Box<dyn Scene>> { Ok(Box::new(GameMenu::new::<HistoryMenu>(context)?)) }
+
+pub struct HistoryMenu {}
+
+impl GameMenuData for HistoryMenu {
+    fn menu_mappings() -> Vec<NewGameMenuItemData> {
+        let opt_player = PLAYER.lock().unwrap();
+        let mut games: Vec<_> = opt_player
+            .iter()
+            .flat_map(|player| player.completed_puzzles.iter())
+            .flat_map(|(puzzle_id, stats)| stats.iter().filter_map(move |stat| Some((puzzle_id, stat.replay_id?, stat.clone()))))
+            .collect();
+        // Most recent first
+        games.sort_by_key(|(_, _, stat)| Reverse(stat.finish_time));
+
+        let mut items = vec![NewGameMenuItemData {
+            variant: NewGameMenuItemDataVariant::TextItem { text: "Personal Bests".to_string() },
+            next_page: Some(Box::new(|c| try_scene(c, "Couldn't Show the Personal Bests", |ctx| StatsScene::new(ctx, None), history))),
+        }];
+        items.extend(games.into_iter().take(MAX_LISTED_GAMES).map(|(puzzle_id, replay_id, stat)| NewGameMenuItemData {
+            variant: NewGameMenuItemDataVariant::TextItem {
+                text: format!(
+                    "{} ({}): {} moves in {}{}",
+                    ImageLoader::title(puzzle_id),
+                    stat.grid_name(),
+                    stat.move_count,
+                    format_duration(stat.duration),
+                    stat.mode_note()
+                ),
+            },
+            next_page: Some(Box::new(move |c| {
+                try_scene(c, "Couldn't Play the Replay", move |ctx| ReplayScene::new(ctx, Replay::load(replay_id)?), history)
+            })),
+        }));
+
+        items.push(NewGameMenuItemData {
+            variant: NewGameMenuItemDataVariant::TextItem { text: "Back".to_string() },
+            next_page: Some(Box::new(back_to_main_menu)),
+        });
+        items
+    }
+
+    fn title() -> String { "Recently Completed Puzzles".to_string() }
+}
@@ -1,55 +1,77 @@
 use std::process::exit;
 
-use ggez::Context;
+use ggez::{Context, GameError, GameResult};
 
 use crate::game::{
     multiplayer::join_scene::JoinMultiplayerScene,
     player::{settings_scene::SettingsScene, PLAYER},
-    puzzle::{puzzle_listing::PuzzleListing, tiles::TileState},
+    puzzle::{
+        custom_board_scene::CustomBoardScene,
+        import_scene::ImportScene,
+        puzzle_listing::PuzzleListing,
+        tiles::{Scramble, TileState},
+    },
+    resources::image_loader::{ImageLoader, PuzzleId},
     scene::Scene,
+    ui::error_scene::{main_menu, try_scene},
 };
 
 use super::{
-    game_menu::GameMenuData,
+    game_menu::{GameMenu, GameMenuData},
+    history_menu::HistoryMenu,
     menu_item_list::{NewGameMenuItemData, NewGameMenuItemDataVariant},
 };
 
-pub fn continue_game(context: &mut Context) -> Box<dyn Scene> {
+fn start_continue_game(context: &mut Context) -> GameResult<TileState> {
     let opt_player = PLAYER.lock().unwrap();
     // Player guaranteed to be some at this point
     let player = opt_player.as_ref().unwrap();
 
+    // The first puzzle the player hasn't finished, or the first one if they've finished them all
+    let ids = ImageLoader::ids();
+    let finished = |id: &PuzzleId| player.completed_puzzles.contains_key(id);
+    let puzzle_id = ids
+        .iter()
+        .find(|id| !finished(id))
+        .or(ids.first())
+        .cloned()
+        .ok_or_else(|| GameError::ResourceLoadError("There are no puzzles".to_string()))?;
+
     let pos = TileState::center_xy(context);
-    let tile_state = Box::new(
-        TileState::new_singleplayer(
-            context,
-            if !player.completed_puzzles.is_empty() {
-                // We'll have to add some kind of check to make sure
-                // that the player hasn't actually completed the entire game,
-                // otherwise this would cause problems.
-                player.completed_puzzles.iter().next_back().expect("Failed to get max completed puzzle").0 + 1
-            } else {
-                0
-            },
-            player.player_settings.num_rows_cols,
-            pos,
-        )
-        .expect("Failed to create TileState"),
-    );
+    TileState::new_singleplayer(context, &puzzle_id, player.player_settings.num_rows_cols(), Scramble::default(), pos)
+}
 
-    tile_state
+pub fn continue_game(context: &mut Context) -> Box<dyn Scene> {
+    try_scene(context, "Couldn't Start the Game", start_continue_game, main_menu)
 }
 
 pub fn join_multiplayer(context: &mut Context) -> Box<dyn Scene> {
-    Box::new(JoinMultiplayerScene::new(context, 0, false).expect("Failed to create join multiplayer scene"))
+    try_scene(
+        context,
+        "Couldn't Join a Multiplayer Game",
+        |ctx| JoinMultiplayerScene::new(ctx, ImageLoader::id_at(0).unwrap_or_default(), Scramble::default(), false),
+        main_menu,
+    )
 }
 
 pub fn settings_scene(context: &mut Context) -> Box<dyn Scene> {
-    Box::new(SettingsScene::new(context, false).expect("Failed to create settings scene"))
+    try_scene(context, "Couldn't Open the Settings", |ctx| SettingsScene::new(ctx, false), main_menu)
 }
 
 pub fn choose_puzzle(context: &mut Context) -> Box<dyn Scene> {
-    Box::new(PuzzleListing::new(context, 0).expect("Failed to create puzzle listing"))
+    try_scene(context, "Couldn't List the Puzzles", |ctx| PuzzleListing::new(ctx, 0), main_menu)
+}
+
+pub fn import_image(context: &mut Context) -> Box<dyn Scene> {
+    try_scene(context, "Couldn't Open the Import", |ctx| ImportScene::new(ctx, None), main_menu)
+}
+
+pub fn custom_board(context: &mut Context) -> Box<dyn Scene> {
+    try_scene(context, "Couldn't Open the Custom Board", CustomBoardScene::new, main_menu)
+}
+
+pub fn history(context: &mut Context) -> Box<dyn Scene> {
+    try_scene(context, "Couldn't Open the History", GameMenu::new::<HistoryMenu>, main_menu)
 }
 
 pub fn quit_game(_: &mut Context) -> Box<dyn Scene> { exit(0); }
@@ -71,6 +93,18 @@ impl GameMenuData for MainMenu {
                 variant: NewGameMenuItemDataVariant::TextItem { text: "Choose a Puzzle".to_string() },
                 next_page: Some(Box::new(choose_puzzle)),
             },
+            NewGameMenuItemData {
+                variant: NewGameMenuItemDataVariant::TextItem { text: "Import Image".to_string() },
+                next_page: Some(Box::new(import_image)),
+            },
+            NewGameMenuItemData {
+                variant: NewGameMenuItemDataVariant::TextItem { text: "Custom Board".to_string() },
+                next_page: Some(Box::new(custom_board)),
+            },
+            NewGameMenuItemData {
+                variant: NewGameMenuItemDataVariant::TextItem { text: "History".to_string() },
+                next_page: Some(Box::new(history)),
+            },
             NewGameMenuItemData {
                 variant: NewGameMenuItemDataVariant::TextItem { text: "Settings".to_string() },
                 next_page: Some(Box::new(settings_scene)),
@@ -67,15 +67,28 @@ impl GameMenuItem {
                 text.pop();
             } else if c == '\n' || c == '\r' {
                 // Prevent enters
-                return;
-            } else if !*is_num {
-                text.push(c);
-            } else if let Ok(_) = (text.to_owned() + &c.to_string()).parse::<u32>() {
+            } else if !*is_num || (text.to_owned() + &c.to_string()).parse::<u32>().is_ok() {
                 text.push(c);
             }
         }
     }
 
+    pub fn contains(&self, point: Vec2) -> bool { Rect { x: self.pos.x, y: self.pos.y, w: self.w, h: self.h }.contains(point) }
+
+    pub fn set_input_value(&mut self, value: String) {
+        if let GameMenuItemVariant::InputItem { text, .. } = &mut self.item_variant {
+            *text = value;
+        }
+    }
+
+    // For images that load after the item is made
+    pub fn set_image(&mut self, new_image: Arc<Image>) {
+        if let GameMenuItemVariant::ImageItem { image, scale_factor, .. } = &mut self.item_variant {
+            *scale_factor = (self.w - 60.0) / new_image.width() as f32;
+            *image = new_image;
+        }
+    }
+
     // You'll have to parse the String to an int yourself ):
     pub fn get_input_value(&mut self) -> Option<String> {
         if let GameMenuItemVariant::InputItem { text, .. } = &self.item_variant {
@@ -259,7 +272,7 @@ impl SlidingPuzzleDrawable for GameMenuItem {
         match &mut self.item_variant {
             GameMenuItemVariant::TextItem { text_mesh } => {
                 let mt_sz = text_mesh.text.measure(ctx).expect("Failed to calculate menu text size");
-                let mt_y = self.pos.y + ((80.0 - mt_sz.y) / 2.0);
+                let mt_y = self.pos.y + ((self.h - mt_sz.y) / 2.0);
 
                 canvas.draw(&text_mesh.text, graphics::DrawParam::from([self.pos.x + 20.0, mt_y]));
             }
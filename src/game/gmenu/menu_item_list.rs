@@ -5,7 +5,7 @@ use ggez::{graphics::Image, Context, GameResult};
 use crate::game::{
     animation::{animatable::Animatable, DrawablePos},
     drawable::Drawable,
-    input::InputAction,
+    input::{mouse::MouseAction, InputAction},
     scene::Scene,
 };
 
@@ -54,7 +54,17 @@ impl GameMenuItemList {
         Ok(Self { items, selected_item, w, h, has_next_scene: false })
     }
 
+    pub fn width(&self) -> f32 { self.w }
+    pub fn selected(&self) -> usize { self.selected_item }
     pub fn height(&self) -> f32 { (self.h + MENU_ITEM_GAP) * self.items.len() as f32 - MENU_ITEM_GAP }
+
+    fn select_item(&mut self, item: usize) {
+        if item != self.selected_item {
+            self.items[self.selected_item].deselect();
+            self.selected_item = item;
+            self.items[self.selected_item].select();
+        }
+    }
 }
 
 impl Drawable for GameMenuItemList {
@@ -72,16 +82,12 @@ impl Scene for GameMenuItemList {
         match key_input {
             InputAction::Up =>
                 if self.selected_item > 0 {
-                    self.items[self.selected_item].deselect();
-                    self.selected_item -= 1;
-                    self.items[self.selected_item].select();
+                    self.select_item(self.selected_item - 1);
                 },
 
             InputAction::Down =>
                 if self.selected_item < self.items.len() - 1 {
-                    self.items[self.selected_item].deselect();
-                    self.selected_item += 1;
-                    self.items[self.selected_item].select();
+                    self.select_item(self.selected_item + 1);
                 },
             InputAction::Select => {
                 self.has_next_scene = true;
@@ -90,11 +96,23 @@ impl Scene for GameMenuItemList {
         }
     }
 
+    fn handle_mouse_event(&mut self, _ctx: &mut Context, mouse_input: MouseAction) {
+        let (MouseAction::Hover(point) | MouseAction::Click(point)) = mouse_input else { return };
+        if let Some(item) = self.items.iter().position(|item| item.contains(point)) {
+            self.select_item(item);
+            if let MouseAction::Click(_) = mouse_input {
+                self.has_next_scene = true;
+            }
+        }
+    }
+
     fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
         if self.has_next_scene {
             if let Some(next_page) = &self.items[self.selected_item].next_page {
                 Some((next_page)(ctx))
             } else {
+                // Don't jump to the next item's page as soon as it gets selected
+                self.has_next_scene = false;
                 None
             }
         } else {
@@ -113,7 +131,7 @@ impl Scene for GameMenuItemList {
 
 impl Animatable<DrawablePos> for GameMenuItemList {
     fn set_state(&mut self, now: DrawablePos) {
-        for (i, mut e) in self.items.iter_mut().enumerate() {
+        for (i, e) in self.items.iter_mut().enumerate() {
             e.pos.y = now.y + ((MENU_ITEM_GAP + self.h) * i as f32);
             e.pos.x = now.x;
         }
@@ -1,4 +1,5 @@
 pub mod game_menu;
+pub mod history_menu;
 pub mod main_menu;
 pub mod menu_item;
 pub mod menu_item_list;
@@ -71,6 +71,10 @@ impl GameControllerInput {
         match i {
             Button::South => Some(InputAction::Select),
             Button::East => Some(InputAction::Cancel),
+            Button::North => Some(InputAction::Hint),
+            Button::West => Some(InputAction::AutoSolve),
+            Button::LeftTrigger => Some(InputAction::Undo),
+            Button::RightTrigger => Some(InputAction::Redo),
             Button::DPadDown => Some(InputAction::Down),
             Button::DPadUp => Some(InputAction::Up),
             Button::DPadRight => Some(InputAction::Right),
@@ -1 +1,65 @@
+use ggez::{
+    glam::Vec2,
+    graphics::{Canvas, Color, DrawMode, Mesh, Rect},
+    Context, GameResult,
+};
 
+use crate::game::{
+    drawable::Drawable,
+    puzzle::tiles::{Move, TilePosition},
+    resources::theme::Theme,
+};
+
+// Highlights the tile the player should slide next, with an arrow pointing
+// the way it should go.
+pub struct HintOverlay {
+    pos: TilePosition,
+    // Where the hinted tile is on the board
+    pub cell: (usize, usize),
+    shade_rect: Mesh,
+    border_rect: Mesh,
+    arrow: Mesh,
+}
+
+impl HintOverlay {
+    pub fn new(ctx: &mut Context, pos: TilePosition, cell: (usize, usize), side_len: u32, mv: Move) -> GameResult<Self> {
+        let side_len = side_len as f32;
+        let tile_rect = Rect { x: 0.0, y: 0.0, w: side_len, h: side_len };
+        let shade_color = Color { a: 0.5, ..Theme::bg_color() };
+
+        // Arrow pointing up, rotated to face the way the tile slides
+        let half = side_len / 4.0;
+        let rotate = |(x, y): (f32, f32)| -> Vec2 {
+            let (x, y) = match mv {
+                Move::Up => (x, y),
+                Move::Down => (-x, -y),
+                Move::Left => (y, -x),
+                Move::Right => (-y, x),
+            };
+            Vec2::new(side_len / 2.0 + x, side_len / 2.0 + y)
+        };
+        let arrow_points: Vec<Vec2> =
+            [(0.0, -half), (half, 0.0), (half / 2.5, 0.0), (half / 2.5, half), (-half / 2.5, half), (-half / 2.5, 0.0), (-half, 0.0)]
+                .into_iter()
+                .map(rotate)
+                .collect();
+
+        Ok(Self {
+            pos,
+            cell,
+            shade_rect: Mesh::new_rectangle(ctx, DrawMode::fill(), tile_rect, shade_color)?,
+            border_rect: Mesh::new_rectangle(ctx, DrawMode::stroke(8.0), tile_rect, Theme::border_color())?,
+            arrow: Mesh::new_polygon(ctx, DrawMode::fill(), &arrow_points, Theme::fg_color())?,
+        })
+    }
+}
+
+impl Drawable for HintOverlay {
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        let pos = Vec2::new(self.pos.x, self.pos.y);
+        canvas.draw(&self.shade_rect, pos);
+        canvas.draw(&self.border_rect, pos);
+        canvas.draw(&self.arrow, pos);
+        Ok(())
+    }
+}
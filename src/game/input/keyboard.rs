@@ -1,4 +1,4 @@
-use ggez::input::keyboard::KeyInput;
+use ggez::input::keyboard::{KeyInput, KeyMods};
 
 use super::InputAction;
 
@@ -7,6 +7,18 @@ impl KeyboardInput {
     pub fn process_key_input(i: KeyInput, repeat: bool) -> Option<InputAction> {
         let vkeycode = i.keycode;
         if let Some(vkeycode) = vkeycode {
+            if !repeat && i.mods.contains(KeyMods::SHIFT) {
+                let line = match vkeycode {
+                    ggez::winit::event::VirtualKeyCode::Left => Some(InputAction::LineLeft),
+                    ggez::winit::event::VirtualKeyCode::Up => Some(InputAction::LineUp),
+                    ggez::winit::event::VirtualKeyCode::Right => Some(InputAction::LineRight),
+                    ggez::winit::event::VirtualKeyCode::Down => Some(InputAction::LineDown),
+                    _ => None,
+                };
+                if line.is_some() {
+                    return line;
+                }
+            }
             if !repeat {
                 return match vkeycode {
                     ggez::winit::event::VirtualKeyCode::Escape => Some(InputAction::Cancel),
@@ -15,6 +27,11 @@ impl KeyboardInput {
                     ggez::winit::event::VirtualKeyCode::Right => Some(InputAction::Right),
                     ggez::winit::event::VirtualKeyCode::Down => Some(InputAction::Down),
                     ggez::winit::event::VirtualKeyCode::Return => Some(InputAction::Select),
+                    ggez::winit::event::VirtualKeyCode::H => Some(InputAction::Hint),
+                    ggez::winit::event::VirtualKeyCode::Z => Some(InputAction::Undo),
+                    ggez::winit::event::VirtualKeyCode::Y => Some(InputAction::Redo),
+                    ggez::winit::event::VirtualKeyCode::S => Some(InputAction::AutoSolve),
+                    ggez::winit::event::VirtualKeyCode::C => Some(InputAction::CopyBoard),
                     _ => None,
                 };
             }
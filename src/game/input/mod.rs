@@ -1,12 +1,26 @@
 pub mod controller;
 pub mod hint_overlay;
 pub mod keyboard;
+pub mod mouse;
 
 pub enum InputAction {
     Up,
     Down,
     Left,
     Right,
+    // Slide every tile between the blank and the edge of the board
+    LineUp,
+    LineDown,
+    LineLeft,
+    LineRight,
     Select,
     Cancel,
+    // Show the next move of a solution
+    Hint,
+    Undo,
+    Redo,
+    // Let the game solve the puzzle by itself
+    AutoSolve,
+    // Copy the board in text notation to share it
+    CopyBoard,
 }
@@ -0,0 +1,40 @@
+use ggez::glam::Vec2;
+
+// Presses that move less than this are clicks rather than drags
+const DRAG_THRESHOLD: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy)]
+pub enum MouseAction {
+    // The pointer moved without a button held down
+    Hover(Vec2),
+    Click(Vec2),
+    Drag { from: Vec2, to: Vec2 },
+}
+
+// Touches are turned into mouse events by ggez, so this handles both.
+#[derive(Default)]
+pub struct MouseInput {
+    pressed_at: Option<Vec2>,
+}
+
+impl MouseInput {
+    pub fn process_press(&mut self, x: f32, y: f32) { self.pressed_at = Some(Vec2::new(x, y)); }
+
+    pub fn process_motion(&mut self, x: f32, y: f32) -> Option<MouseAction> {
+        if self.pressed_at.is_none() {
+            Some(MouseAction::Hover(Vec2::new(x, y)))
+        } else {
+            None
+        }
+    }
+
+    pub fn process_release(&mut self, x: f32, y: f32) -> Option<MouseAction> {
+        let from = self.pressed_at.take()?;
+        let to = Vec2::new(x, y);
+        if from.distance(to) < DRAG_THRESHOLD {
+            Some(MouseAction::Click(from))
+        } else {
+            Some(MouseAction::Drag { from, to })
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use self::input::controller::GameControllerInput;
 use self::input::keyboard::KeyboardInput;
+use self::input::mouse::MouseInput;
 use self::resources::theme::Theme;
-use self::resources::ResourceManager;
+use self::resources::{ResourceManager, StartOptions};
 use self::scene::Scene;
 use ggez::event;
 use ggez::event::Axis;
@@ -9,7 +10,7 @@ use ggez::event::Button;
 use ggez::glam::Vec2;
 use ggez::graphics;
 
-use ggez::event::GamepadId;
+use ggez::event::MouseButton;
 use ggez::graphics::DrawMode;
 use ggez::graphics::Mesh;
 use ggez::graphics::Rect;
@@ -18,9 +19,11 @@ use ggez::{Context, GameResult};
 use keyframe::functions::EaseInOut;
 use keyframe::keyframes;
 use keyframe::AnimationSequence;
+use std::path::PathBuf;
 
 pub mod animation;
 pub mod drawable;
+pub mod event_loop;
 pub mod gmenu;
 pub mod input;
 pub mod player;
@@ -40,27 +43,44 @@ pub struct GameState {
 
     // Input related
     gc_inp: GameControllerInput,
+    mouse_inp: MouseInput,
 
     // Scene transition animation variables
     scene_transition: Option<AnimationSequence<f32>>,
 }
 
 impl GameState {
-    pub fn new(context: &mut Context) -> GameResult<Self> {
+    pub fn new(context: &mut Context, options: StartOptions) -> GameResult<Self> {
         // Loop through and make the tiles
         Ok(Self {
-            current_scene: Box::new(ResourceManager::new(context)?),
+            current_scene: Box::new(ResourceManager::new(context, options)?),
             prev_scene: None,
             set_winsize: false,
             scene_transition: None,
             gc_inp: GameControllerInput::default(),
+            mouse_inp: MouseInput::default(),
         })
     }
+
+    // These are called by event_loop::run
+    pub fn file_dropped_event(&mut self, ctx: &mut Context, path: PathBuf) { self.current_scene.file_dropped_event(ctx, path); }
+
+    pub fn gamepad_button(&mut self, ctx: &mut Context, btn: Button) {
+        if let Some(inp) = self.gc_inp.process_button_input(btn) {
+            self.current_scene.handle_input_event(ctx, inp);
+        }
+    }
+
+    pub fn gamepad_axis(&mut self, ctx: &mut Context, axis: Axis, value: f32) {
+        if let Some(inp) = self.gc_inp.process_axis_input(axis, value) {
+            self.current_scene.handle_input_event(ctx, inp);
+        }
+    }
 }
 
 impl event::EventHandler<ggez::GameError> for GameState {
     fn update(&mut self, ctx: &mut Context) -> GameResult {
-        if let None = self.scene_transition {
+        if self.scene_transition.is_none() {
             if let Some(next_scene) = self.current_scene.next_scene(ctx) {
                 self.prev_scene = Some(std::mem::replace(&mut self.current_scene, next_scene));
                 self.scene_transition =
@@ -98,6 +118,7 @@ impl event::EventHandler<ggez::GameError> for GameState {
         } else {
             self.current_scene.draw(ctx, &mut canvas)?;
         }
+        ui::error_toast::draw(ctx, &mut canvas)?;
         canvas.finish(ctx)
     }
 
@@ -113,16 +134,25 @@ impl event::EventHandler<ggez::GameError> for GameState {
         Ok(())
     }
 
-    fn gamepad_button_down_event(&mut self, ctx: &mut Context, btn: Button, _id: GamepadId) -> Result<(), ggez::GameError> {
-        if let Some(inp) = self.gc_inp.process_button_input(btn) {
-            self.current_scene.handle_input_event(ctx, inp);
+    fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) -> GameResult {
+        if let MouseButton::Left = button {
+            self.mouse_inp.process_press(x, y);
         }
         Ok(())
     }
 
-    fn gamepad_axis_event(&mut self, ctx: &mut Context, axis: Axis, value: f32, _id: ggez::event::GamepadId) -> GameResult {
-        if let Some(inp) = self.gc_inp.process_axis_input(axis, value) {
-            self.current_scene.handle_input_event(ctx, inp);
+    fn mouse_button_up_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) -> GameResult {
+        if let MouseButton::Left = button {
+            if let Some(inp) = self.mouse_inp.process_release(x, y) {
+                self.current_scene.handle_mouse_event(ctx, inp);
+            }
+        }
+        Ok(())
+    }
+
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) -> GameResult {
+        if let Some(inp) = self.mouse_inp.process_motion(x, y) {
+            self.current_scene.handle_mouse_event(ctx, inp);
         }
         Ok(())
     }
@@ -10,15 +10,18 @@ use keyframe::{functions::EaseInOut, keyframes, AnimationSequence};
 use crate::game::{
     animation::DrawablePos,
     drawable::Drawable,
-    input::InputAction,
+    input::{mouse::MouseAction, InputAction},
     player::PLAYER,
     puzzle::{
         puzzle_view::PuzzleView,
-        tiles::{tile_multiplayer::TileMultiplayerTransport, TileState},
+        tiles::{tile_multiplayer::TileMultiplayerTransport, Scramble, TileState},
     },
-    resources::theme::Theme,
+    resources::{image_loader::PuzzleId, theme::Theme},
     scene::Scene,
-    ui::uitext::UIText,
+    ui::{
+        error_scene::{main_menu, try_scene},
+        uitext::UIText,
+    },
 };
 
 use super::transport::MultiplayerTransport;
@@ -41,30 +44,35 @@ pub struct MultiplayerGameView {
     winner_text: UIText,
     winner_anim: AnimationSequence<f32>,
     winner: Option<Winner>,
+    peer_username: String,
+    // The local player's game goes in their statistics once they finish
+    recorded: bool,
 
     game_cancelled: bool,
-    img_num: usize,
+    puzzle_id: PuzzleId,
 }
 
 impl MultiplayerGameView {
     pub fn new(
-        context: &mut Context, transport: MultiplayerTransport, img_num: usize, num_rows_cols: usize, peer_username: String,
+        context: &mut Context, transport: MultiplayerTransport, puzzle_id: PuzzleId, num_rows_cols: (usize, usize), scramble: Scramble,
+        peer_username: String,
     ) -> GameResult<Self> {
         let transport = Arc::new(transport);
         Ok(Self {
-            img_num,
             user_tile_state: TileState::new(
                 context,
-                img_num,
+                &puzzle_id,
                 num_rows_cols,
+                scramble,
                 (0.0, 0.0),
                 TileMultiplayerTransport::new(Some(transport.clone())),
                 false,
             )?,
             peer_tile_state: TileState::new(
                 context,
-                img_num,
+                &puzzle_id,
                 num_rows_cols,
+                scramble,
                 (850.0, 0.0),
                 TileMultiplayerTransport::new(Some(transport)),
                 true,
@@ -97,8 +105,11 @@ impl MultiplayerGameView {
                 38.0,
                 DrawablePos { x: 90.0, y: 90.0 },
             ),
-            peer_user_text: UIText::new(peer_username, Theme::fg_color(), 38.0, DrawablePos { x: 90.0 + 835.0, y: 90.0 }),
+            peer_user_text: UIText::new(peer_username.clone(), Theme::fg_color(), 38.0, DrawablePos { x: 90.0 + 835.0, y: 90.0 }),
+            peer_username,
+            recorded: false,
             game_cancelled: false,
+            puzzle_id,
         })
     }
 }
@@ -106,30 +117,35 @@ impl MultiplayerGameView {
 impl Scene for MultiplayerGameView {
     fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
         if self.game_cancelled {
-            Some(Box::new(PuzzleView::new(ctx, self.img_num).expect("Failed to create puzzle view")))
+            let puzzle_id = self.puzzle_id.clone();
+            Some(try_scene(ctx, "Couldn't Show the Puzzle", move |ctx| PuzzleView::new(ctx, puzzle_id.clone()), main_menu))
         } else {
             None
         }
     }
     fn update(&mut self, ctx: &mut Context) -> GameResult {
         // Logic to check if there is a winner
-        if let None = self.winner {
-            if self.peer_tile_state.puzzle_statistics.is_some() && self.user_tile_state.puzzle_statistics.is_none() {
+        if self.winner.is_none() {
+            match (&self.peer_tile_state.puzzle_statistics, &self.user_tile_state.puzzle_statistics) {
                 // Peer won
-                self.winner = Some(Winner::Peer)
-            } else if self.peer_tile_state.puzzle_statistics.is_none() && self.user_tile_state.puzzle_statistics.is_some() {
+                (Some(_), None) => self.winner = Some(Winner::Peer),
                 // Local won
-                self.winner = Some(Winner::User)
-            } else if self.peer_tile_state.puzzle_statistics.is_some() && self.user_tile_state.puzzle_statistics.is_some() {
+                (None, Some(_)) => self.winner = Some(Winner::User),
                 // Time based reconciliation
-                let peer_fin_time = self.peer_tile_state.puzzle_statistics.as_ref().unwrap().finish_time;
-
-                let user_fin_time = self.user_tile_state.puzzle_statistics.as_ref().unwrap().finish_time;
-                if peer_fin_time > user_fin_time {
-                    self.winner = Some(Winner::Peer)
-                } else {
-                    self.winner = Some(Winner::User)
-                }
+                (Some(peer_stats), Some(user_stats)) =>
+                    if peer_stats.finish_time > user_stats.finish_time {
+                        self.winner = Some(Winner::Peer)
+                    } else {
+                        self.winner = Some(Winner::User)
+                    },
+                (None, None) => {}
+            }
+        }
+        // Finishing second still counts, as a loss
+        if !self.recorded && self.user_tile_state.puzzle_statistics.is_some() {
+            if let Some(winner) = &self.winner {
+                self.user_tile_state.record_completion(Some((matches!(winner, Winner::User), self.peer_username.clone())));
+                self.recorded = true;
             }
         }
 
@@ -142,6 +158,10 @@ impl Scene for MultiplayerGameView {
         }
         self.user_tile_state.handle_input_event(ctx, key_input);
     }
+
+    fn handle_mouse_event(&mut self, ctx: &mut Context, mouse_input: MouseAction) {
+        self.user_tile_state.handle_mouse_event(ctx, mouse_input);
+    }
 }
 
 impl Drawable for MultiplayerGameView {
@@ -10,8 +10,20 @@ use ggez::{Context, GameError, GameResult};
 use log::info;
 
 use crate::game::{
-    animation::DrawablePos, drawable::Drawable, input::InputAction, player::PLAYER, puzzle::puzzle_view::PuzzleView,
-    resources::theme::Theme, scene::Scene, ui::uitext::UIText,
+    animation::DrawablePos,
+    drawable::Drawable,
+    input::InputAction,
+    player::PLAYER,
+    puzzle::puzzle_view::{back_to_puzzle, PuzzleView},
+    puzzle::tiles::Scramble,
+    resources::image_loader::PuzzleId,
+    resources::theme::Theme,
+    scene::Scene,
+    ui::{
+        error_scene::{main_menu, show_error, try_scene},
+        error_toast,
+        uitext::UIText,
+    },
 };
 
 use super::{game_view::MultiplayerGameView, transport::MultiplayerTransport, MultiplayerGameMessage};
@@ -24,7 +36,9 @@ pub struct JoinMultiplayerScene {
     conn_string: Option<UIText>,
     transport: Option<MultiplayerTransport>,
     clipboard: Clipboard,
-    puzzle_num: usize,
+    puzzle_id: PuzzleId,
+    // Only used by the creator, the joining player gets it in StartGame
+    scramble: Scramble,
     game_cancelled: bool,
     game_started: Option<MultiplayerGameMessage>,
 
@@ -32,7 +46,7 @@ pub struct JoinMultiplayerScene {
 }
 
 impl JoinMultiplayerScene {
-    pub fn new(_ctx: &mut Context, puzzle_num: usize, creator: bool) -> GameResult<Self> {
+    pub fn new(_ctx: &mut Context, puzzle_id: PuzzleId, scramble: Scramble, creator: bool) -> GameResult<Self> {
         let header = UIText::new(
             if creator { "Create Multiplayer Game" } else { "Join Multiplayer Game" }.to_string(),
             Theme::fg_color(),
@@ -53,12 +67,53 @@ impl JoinMultiplayerScene {
             conn_string: None,
             transport: if creator { Some(MultiplayerTransport::create_game(None)?) } else { None },
             clipboard: Clipboard::new().map_err(|_| GameError::CustomError("Failed to get game clipboard".to_string()))?,
-            puzzle_num,
+            puzzle_id,
+            scramble,
             game_cancelled: false,
             game_started: None,
             peer_username: None,
         })
     }
+
+    // Reads the other player's connection string from the clipboard
+    fn connect(&mut self) -> GameResult {
+        let conn_str = self
+            .clipboard
+            .get_text()
+            .map_err(|e| GameError::CustomError(format!("Couldn't get the connection string from the clipboard: {}", e)))?;
+        if self.creator {
+            self.transport
+                .as_ref()
+                .ok_or_else(|| GameError::CustomError("The game hasn't been created yet".to_string()))?
+                .event_push_buffer
+                .send(MultiplayerGameMessage::ConnectionString(conn_str))
+                .map_err(|_| GameError::CustomError("The connection was lost".to_string()))?;
+        } else {
+            let transport = MultiplayerTransport::create_game(Some(conn_str))?;
+            let username = PLAYER.lock().unwrap().as_ref().unwrap().username();
+            transport
+                .event_push_buffer
+                .send(MultiplayerGameMessage::Hello { username })
+                .map_err(|_| GameError::CustomError("The connection was lost".to_string()))?;
+            self.transport = Some(transport);
+        }
+        Ok(())
+    }
+
+    // The transport goes to the game, so this is only done once
+    fn start_game(&mut self, ctx: &mut Context) -> GameResult<MultiplayerGameView> {
+        let Some(MultiplayerGameMessage::StartGame { puzzle_id, num_rows, num_cols, scramble, host_username }) = self.game_started.take()
+        else {
+            return Err(GameError::CustomError("The game hasn't been started".to_string()));
+        };
+        let transport = self.transport.take().ok_or_else(|| GameError::CustomError("The connection was lost".to_string()))?;
+        let peer_username = if self.creator {
+            self.peer_username.take().ok_or_else(|| GameError::CustomError("The other player didn't say who they are".to_string()))?
+        } else {
+            host_username
+        };
+        MultiplayerGameView::new(ctx, transport, puzzle_id, (num_rows, num_cols), scramble, peer_username)
+    }
 }
 
 impl Drawable for JoinMultiplayerScene {
@@ -80,20 +135,16 @@ impl Drawable for JoinMultiplayerScene {
 
 impl Scene for JoinMultiplayerScene {
     fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
-        if let Some(MultiplayerGameMessage::StartGame { img_num, num_rows_cols, host_username }) = &self.game_started {
-            let transport = self.transport.take().unwrap();
-            Some(Box::new(
-                MultiplayerGameView::new(
-                    ctx,
-                    transport,
-                    *img_num,
-                    *num_rows_cols,
-                    if self.creator { self.peer_username.take().unwrap() } else { host_username.clone() },
-                )
-                .expect("Failed to create multiplayer game view"),
-            ))
+        if self.game_started.is_some() {
+            let back = back_to_puzzle(&self.puzzle_id);
+            Some(match self.start_game(ctx) {
+                Ok(game_view) => Box::new(game_view),
+                // The connection went with it, so it can't be tried again
+                Err(e) => show_error(ctx, "Couldn't Start the Multiplayer Game", &e, back),
+            })
         } else if self.game_cancelled {
-            Some(Box::new(PuzzleView::new(ctx, self.puzzle_num).expect("Failed to return to puzzle listing")))
+            let puzzle_id = self.puzzle_id.clone();
+            Some(try_scene(ctx, "Couldn't Show the Puzzle", move |ctx| PuzzleView::new(ctx, puzzle_id.clone()), main_menu))
         } else {
             None
         }
@@ -104,11 +155,16 @@ impl Scene for JoinMultiplayerScene {
             if let Ok(event) = transport.event_buffer.try_recv() {
                 match event {
                     MultiplayerGameMessage::ConnectionString(s) => {
-                        self.clipboard
-                            .set_text(&s)
-                            .map_err(|_| GameError::CustomError("Failed to copy connection string to clipboard".to_string()))?;
+                        // Shown instead, so it can still be copied by hand
+                        let copied = match self.clipboard.set_text(&s) {
+                            Ok(()) => "Copied connection string to clipboard!".to_string(),
+                            Err(e) => {
+                                error_toast::report(format!("Couldn't copy the connection string: {}", e));
+                                format!("Connection string:\n{}", s)
+                            }
+                        };
                         self.conn_string = Some(UIText::new(
-                            "Copied connection string to clipboard!".to_string(),
+                            copied,
                             Theme::fg_color(),
                             48.0,
                             DrawablePos { x: 90.0, y: self.header.text.measure(ctx)?.y + 90.0 },
@@ -122,18 +178,18 @@ impl Scene for JoinMultiplayerScene {
 
                         self.peer_username = Some(username);
 
-                        self.game_started = Some(MultiplayerGameMessage::StartGame {
-                            img_num: self.puzzle_num,
-                            num_rows_cols: player.player_settings.num_rows_cols,
+                        let start = MultiplayerGameMessage::StartGame {
+                            puzzle_id: self.puzzle_id.clone(),
+                            num_rows: player.player_settings.num_rows,
+                            num_cols: player.player_settings.num_cols,
+                            scramble: self.scramble,
                             host_username: player.username(),
-                        });
-                        // TODO handle this better
-                        self.transport
-                            .as_ref()
-                            .unwrap()
-                            .event_push_buffer
-                            .send(self.game_started.as_ref().unwrap().clone())
-                            .expect("Failed to send game start event to peer");
+                        };
+                        if transport.event_push_buffer.send(start.clone()).is_err() {
+                            error_toast::report("Couldn't start the game, the connection was lost".to_string());
+                        } else {
+                            self.game_started = Some(start);
+                        }
                     }
                     MultiplayerGameMessage::StartGame { .. } => {
                         self.game_started = Some(event.clone());
@@ -151,36 +207,10 @@ impl Scene for JoinMultiplayerScene {
             InputAction::Cancel => {
                 self.game_cancelled = true;
             }
-            InputAction::Select => {
-                let conn_str = self
-                    .clipboard
-                    .get_text()
-                    .map_err(|_| GameError::CustomError("Failed to get connection string from clipboard".to_string()))
-                    // Make this method return a result so this doesn't happen
-                    .unwrap();
-                if self.creator {
-                    if let Err(_e) =
-                        self.transport.as_ref().unwrap().event_push_buffer.send(MultiplayerGameMessage::ConnectionString(conn_str))
-                    {
-                        println!("failed to send event");
-                    }
-                } else {
-                    self.transport = Some(MultiplayerTransport::create_game(Some(conn_str)).unwrap());
-
-                    self.transport
-                        .as_ref()
-                        .unwrap()
-                        .event_push_buffer
-                        .send(MultiplayerGameMessage::Hello {
-                            username: {
-                                let opt_p = PLAYER.lock().unwrap();
-                                let p = opt_p.as_ref().unwrap();
-                                p.username()
-                            },
-                        })
-                        .unwrap();
-                }
-            }
+            InputAction::Select =>
+                if let Err(e) = self.connect() {
+                    error_toast::report_error("Couldn't connect", &e);
+                },
             _ => {}
         }
     }
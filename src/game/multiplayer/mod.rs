@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use super::player::PuzzleStatistics;
+use super::{
+    player::PuzzleStatistics,
+    puzzle::tiles::{Board, Scramble},
+    resources::image_loader::PuzzleId,
+};
 
 pub mod game_view;
 pub mod join_scene;
@@ -12,7 +16,8 @@ pub enum MultiplayerGameMessage {
     Hello { username: String },
     CloseConnection,
     DeleteRandomTile((usize, usize)),
-    StartGame { img_num: usize, num_rows_cols: usize, host_username: String },
+    SetBoard(Board),
+    StartGame { puzzle_id: PuzzleId, num_rows: usize, num_cols: usize, scramble: Scramble, host_username: String },
     SwapTiles { i1j1: (usize, usize), i2j2: (usize, usize), duration: f32 },
     ScramblingFinished,
     GameCompleted(PuzzleStatistics),
@@ -0,0 +1,172 @@
+// Rename or delete a profile. Deleting takes a second press to be sure, since
+// the profile's statistics and replays go with it.
+
+use ggez::{graphics::Color, Context, GameError, GameResult};
+use uuid::Uuid;
+
+use crate::game::{
+    animation::DrawablePos,
+    drawable::Drawable,
+    gmenu::menu_item_list::{GameMenuItemList, NewGameMenuItemData, NewGameMenuItemDataVariant},
+    input::{mouse::MouseAction, InputAction},
+    resources::theme::Theme,
+    scene::Scene,
+    ui::{error_message, error_scene::try_scene, uitext::UIText},
+};
+
+use super::{
+    profiles_scene::{back_to_profiles, ProfilesScene},
+    settings_scene::{back_to_settings, SettingsScene},
+    Player, PLAYER,
+};
+
+// Indices into options
+const USERNAME_ITEM: usize = 0;
+const RENAME_ITEM: usize = 1;
+const DELETE_ITEM: usize = 2;
+
+pub struct EditProfileScene {
+    id: Uuid,
+    title_text: UIText,
+    options: GameMenuItemList,
+    // How the last rename went, or what pressing delete again does
+    status_text: Option<UIText>,
+
+    confirm_delete: bool,
+    deleted: bool,
+    back: bool,
+}
+
+impl EditProfileScene {
+    pub fn new(ctx: &mut Context, id: Uuid) -> GameResult<Self> {
+        let player = Player::load_profile(id)?;
+        let title_text = UIText::new(format!("Edit {}", player.username), Theme::fg_color(), 78.0, DrawablePos { x: 90.0, y: 90.0 });
+        let t_sz = title_text.text.measure(ctx)?;
+
+        let options = GameMenuItemList::new(
+            ctx,
+            vec![
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::InputItem {
+                        prompt: "Username".to_string(),
+                        is_num: false,
+                        initial_value: player.username,
+                    },
+                    next_page: None,
+                },
+                NewGameMenuItemData { variant: NewGameMenuItemDataVariant::TextItem { text: "Rename".to_string() }, next_page: None },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::TextItem { text: "Delete Profile".to_string() },
+                    next_page: None,
+                },
+            ],
+            90.0,
+            t_sz.y + 140.0,
+            900.0,
+            110.0,
+        )?;
+
+        Ok(Self { id, title_text, options, status_text: None, confirm_delete: false, deleted: false, back: false })
+    }
+
+    fn show_status(&mut self, status: String, color: Color) {
+        let y = self.options.items[DELETE_ITEM].pos.y + 150.0;
+        self.status_text = Some(UIText::new(status, color, 38.0, DrawablePos { x: 90.0, y }));
+    }
+
+    fn rename(&mut self) -> GameResult<String> {
+        let username = self.options.items[USERNAME_ITEM].get_input_value().unwrap().trim().to_string();
+        if username.is_empty() {
+            return Err(GameError::CustomError("Enter a username first".to_string()));
+        }
+        if Player::username_taken(&username, Some(self.id)) {
+            return Err(GameError::CustomError(format!("There's already a profile called {}", username)));
+        }
+
+        // The profile being played has to change too, or its next save would undo this
+        let mut opt_player = PLAYER.lock().unwrap();
+        match opt_player.as_mut().filter(|player| player.id == self.id) {
+            Some(player) => {
+                player.username = username.clone();
+                player.save()?;
+            }
+            None => Player { username: username.clone(), ..Player::load_profile(self.id)? }.save()?,
+        }
+        Ok(username)
+    }
+
+    fn delete(&mut self) {
+        if !self.confirm_delete {
+            self.confirm_delete = true;
+            let username = Player::load_profile(self.id).map_or("this profile".to_string(), |player| player.username);
+            return self.show_status(format!("Press Delete again to delete {} and all of its games", username), Theme::error_color());
+        }
+        match Player::delete_profile(self.id) {
+            Ok(()) => self.deleted = true,
+            Err(e) => self.show_status(format!("Couldn't delete the profile: {}", error_message(&e)), Theme::error_color()),
+        }
+    }
+
+    fn activate_selected(&mut self) {
+        match self.options.selected() {
+            RENAME_ITEM => {
+                self.confirm_delete = false;
+                match self.rename() {
+                    Ok(username) => {
+                        self.title_text = UIText::new(format!("Edit {}", username), Theme::fg_color(), 78.0, self.title_text.pos);
+                        self.show_status(format!("Renamed to {}", username), Theme::fg_color());
+                    }
+                    Err(GameError::CustomError(e)) => self.show_status(e, Theme::error_color()),
+                    Err(e) => self.show_status(format!("Couldn't rename the profile: {}", error_message(&e)), Theme::error_color()),
+                }
+            }
+            DELETE_ITEM => self.delete(),
+            _ => {}
+        }
+    }
+}
+
+impl Drawable for EditProfileScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut ggez::graphics::Canvas) -> GameResult {
+        self.title_text.draw(ctx, canvas)?;
+        self.options.draw(ctx, canvas)?;
+        if let Some(status_text) = &mut self.status_text {
+            status_text.draw(ctx, canvas)?;
+        }
+        Ok(())
+    }
+}
+
+impl Scene for EditProfileScene {
+    fn handle_input_event(&mut self, ctx: &mut Context, key_input: InputAction) {
+        match key_input {
+            InputAction::Cancel => self.back = true,
+            InputAction::Select => self.activate_selected(),
+            _ => {}
+        }
+        self.options.handle_input_event(ctx, key_input);
+    }
+
+    fn handle_mouse_event(&mut self, ctx: &mut Context, mouse_input: MouseAction) {
+        self.options.handle_mouse_event(ctx, mouse_input);
+        if let MouseAction::Click(point) = mouse_input {
+            if self.options.items[self.options.selected()].contains(point) {
+                self.activate_selected();
+            }
+        }
+    }
+
+    fn text_input_event(&mut self, ctx: &mut Context, c: char) { self.options.text_input_event(ctx, c); }
+
+    fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
+        if self.deleted && Player::profiles().is_empty() {
+            // Nobody left, start over like a new player
+            Some(try_scene(ctx, "Couldn't Make a New Profile", |ctx| SettingsScene::new(ctx, true), back_to_profiles))
+        } else if self.back || self.deleted {
+            Some(try_scene(ctx, "Couldn't Return to the Profiles", ProfilesScene::new, back_to_settings(true)))
+        } else {
+            // None of the items have pages of their own
+            self.options.next_scene(ctx)
+        }
+    }
+}
@@ -0,0 +1,303 @@
+// Older player.dat formats, newest first. Each one converts into the version
+// after it, see save_file.rs.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use chrono::{DateTime, Local};
+use serde::{
+    de::{DeserializeSeed, Error},
+    Deserialize, Deserializer,
+};
+use uuid::Uuid;
+
+use crate::game::resources::image_loader::{ImageFit, PuzzleId};
+
+use super::{save_file::SAVE_VERSION, GameMode, Player, PlayerSettings, PuzzleStatistics, DEFAULT_AUTO_SOLVE_SPEED};
+
+// Reads a player in the shape the given version saved it in and migrates it to
+// this version. It's the same for every format, player.dat is bincode and
+// profile exports are JSON or TOML.
+pub(super) struct SavedAs(pub u32);
+
+impl<'de> DeserializeSeed<'de> for SavedAs {
+    type Value = Player;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Player, D::Error> {
+        match self.0 {
+            0 => LegacyPlayer::deserialize(deserializer)
+                .map(PlayerV1::from)
+                .map(PlayerV2::from)
+                .map(PlayerV3::from)
+                .map(PlayerV4::from)
+                .map(Player::from),
+            1 => PlayerV1::deserialize(deserializer).map(PlayerV2::from).map(PlayerV3::from).map(PlayerV4::from).map(Player::from),
+            2 => PlayerV2::deserialize(deserializer).map(PlayerV3::from).map(PlayerV4::from).map(Player::from),
+            3 => PlayerV3::deserialize(deserializer).map(PlayerV4::from).map(Player::from),
+            4 => PlayerV4::deserialize(deserializer).map(Player::from),
+            SAVE_VERSION => Player::deserialize(deserializer),
+            version => Err(D::Error::custom(format!("version {} is from a newer version of the game", version))),
+        }
+    }
+}
+
+// Version 4, from before attempts were counted
+#[derive(Deserialize)]
+struct PlayerV4 {
+    id: Uuid,
+    username: String,
+    completed_puzzles: BTreeMap<PuzzleId, Vec<PuzzleStatistics>>,
+    auto_solves: BTreeMap<PuzzleId, u32>,
+    player_settings: PlayerSettings,
+}
+
+// Games that weren't finished weren't saved, so the finished ones are all the
+// attempts there's a record of
+impl From<PlayerV4> for Player {
+    fn from(legacy: PlayerV4) -> Self {
+        let attempts = legacy.completed_puzzles.iter().map(|(puzzle_id, stats)| (puzzle_id.clone(), stats.len() as u32)).collect();
+        Self {
+            id: legacy.id,
+            username: legacy.username,
+            completed_puzzles: legacy.completed_puzzles,
+            auto_solves: legacy.auto_solves,
+            attempts,
+            player_settings: legacy.player_settings,
+        }
+    }
+}
+
+// Version 3, auto-solves were saved as games
+#[derive(Deserialize)]
+enum GameModeV3 {
+    Singleplayer,
+    Hinted,
+    AutoSolved,
+    MultiplayerWin,
+    MultiplayerLoss,
+}
+
+#[derive(Deserialize)]
+struct PuzzleStatisticsV3 {
+    finish_time: DateTime<Local>,
+    duration: Duration,
+    move_count: u32,
+    multi_tile_move_count: u32,
+    hints_used: u32,
+    replay_id: Option<Uuid>,
+    grid: Option<(usize, usize)>,
+    mode: GameModeV3,
+    scramble_seed: Option<u64>,
+    opponent: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PlayerV3 {
+    id: Uuid,
+    username: String,
+    completed_puzzles: BTreeMap<PuzzleId, Vec<PuzzleStatisticsV3>>,
+    player_settings: PlayerSettings,
+}
+
+// Auto-solves become a count for each puzzle
+impl From<PlayerV3> for PlayerV4 {
+    fn from(legacy: PlayerV3) -> Self {
+        let mut completed_puzzles = BTreeMap::new();
+        let mut auto_solves = BTreeMap::new();
+        for (puzzle_id, stats) in legacy.completed_puzzles {
+            let mut games = vec![];
+            for stat in stats {
+                let mode = match stat.mode {
+                    GameModeV3::Singleplayer => GameMode::Singleplayer,
+                    GameModeV3::Hinted => GameMode::Hinted,
+                    GameModeV3::MultiplayerWin => GameMode::MultiplayerWin,
+                    GameModeV3::MultiplayerLoss => GameMode::MultiplayerLoss,
+                    GameModeV3::AutoSolved => {
+                        *auto_solves.entry(puzzle_id.clone()).or_default() += 1;
+                        continue;
+                    }
+                };
+                games.push(PuzzleStatistics {
+                    finish_time: stat.finish_time,
+                    duration: stat.duration,
+                    move_count: stat.move_count,
+                    multi_tile_move_count: stat.multi_tile_move_count,
+                    hints_used: stat.hints_used,
+                    replay_id: stat.replay_id,
+                    grid: stat.grid,
+                    mode,
+                    scramble_seed: stat.scramble_seed,
+                    opponent: stat.opponent,
+                });
+            }
+            if !games.is_empty() {
+                completed_puzzles.insert(puzzle_id, games);
+            }
+        }
+        Self { id: legacy.id, username: legacy.username, completed_puzzles, auto_solves, player_settings: legacy.player_settings }
+    }
+}
+
+// Games from before the grid size and how the game was played were recorded.
+// Auto-solved and multiplayer games weren't saved back then.
+#[derive(Deserialize)]
+struct PuzzleStatisticsV1 {
+    finish_time: DateTime<Local>,
+    duration: Duration,
+    move_count: u32,
+    multi_tile_move_count: u32,
+    hints_used: u32,
+    replay_id: Option<Uuid>,
+}
+
+impl From<PuzzleStatisticsV1> for PuzzleStatisticsV3 {
+    fn from(legacy: PuzzleStatisticsV1) -> Self {
+        Self {
+            finish_time: legacy.finish_time,
+            duration: legacy.duration,
+            move_count: legacy.move_count,
+            multi_tile_move_count: legacy.multi_tile_move_count,
+            hints_used: legacy.hints_used,
+            replay_id: legacy.replay_id,
+            // Filled in from the replay once the save's read, see save_file::read
+            grid: None,
+            mode: if legacy.hints_used > 0 { GameModeV3::Hinted } else { GameModeV3::Singleplayer },
+            scramble_seed: None,
+            opponent: None,
+        }
+    }
+}
+
+// Version 2, puzzles had ids
+#[derive(Deserialize)]
+struct PlayerSettingsV2 {
+    num_rows: usize,
+    num_cols: usize,
+    undo_counts_as_move: bool,
+    auto_solve_speed: u32,
+    image_fit: ImageFit,
+}
+
+#[derive(Deserialize)]
+struct PlayerV2 {
+    id: Uuid,
+    username: String,
+    completed_puzzles: BTreeMap<PuzzleId, Vec<PuzzleStatisticsV1>>,
+    player_settings: PlayerSettingsV2,
+}
+
+impl From<PlayerV2> for PlayerV3 {
+    fn from(legacy: PlayerV2) -> Self {
+        let settings = legacy.player_settings;
+        Self {
+            id: legacy.id,
+            username: legacy.username,
+            completed_puzzles: legacy
+                .completed_puzzles
+                .into_iter()
+                .map(|(puzzle_id, stats)| (puzzle_id, stats.into_iter().map(PuzzleStatisticsV3::from).collect()))
+                .collect(),
+            player_settings: PlayerSettings {
+                num_rows: settings.num_rows,
+                num_cols: settings.num_cols,
+                undo_counts_as_move: settings.undo_counts_as_move,
+                auto_solve_speed: settings.auto_solve_speed,
+                image_fit: settings.image_fit,
+            },
+        }
+    }
+}
+
+// Version 1, rows and columns were set apart. Puzzles didn't have ids yet,
+// they were numbered by their position in /images and the numbered images are
+// named after that number.
+#[derive(Deserialize)]
+struct PlayerSettingsV1 {
+    num_rows: usize,
+    num_cols: usize,
+    undo_counts_as_move: bool,
+    auto_solve_speed: u32,
+}
+
+#[derive(Deserialize)]
+struct PlayerV1 {
+    id: Uuid,
+    username: String,
+    completed_puzzles: BTreeMap<usize, Vec<PuzzleStatisticsV1>>,
+    player_settings: PlayerSettingsV1,
+}
+
+impl From<PlayerV1> for PlayerV2 {
+    fn from(legacy: PlayerV1) -> Self {
+        let settings = legacy.player_settings;
+        Self {
+            id: legacy.id,
+            username: legacy.username,
+            completed_puzzles: legacy.completed_puzzles.into_iter().map(|(num, stats)| (PuzzleId(num.to_string()), stats)).collect(),
+            player_settings: PlayerSettingsV2 {
+                num_rows: settings.num_rows,
+                num_cols: settings.num_cols,
+                undo_counts_as_move: settings.undo_counts_as_move,
+                auto_solve_speed: settings.auto_solve_speed,
+                image_fit: ImageFit::default(),
+            },
+        }
+    }
+}
+
+// Version 0, the first release. The board was always square and games only
+// kept their time and moves. Puzzles are numbered like version 1's.
+#[derive(Deserialize)]
+struct LegacyPlayerSettings {
+    num_rows_cols: usize,
+}
+
+#[derive(Deserialize)]
+struct LegacyPuzzleStatistics {
+    finish_time: DateTime<Local>,
+    duration: Duration,
+    move_count: u32,
+}
+
+#[derive(Deserialize)]
+struct LegacyPlayer {
+    id: Uuid,
+    username: String,
+    completed_puzzles: BTreeMap<usize, Vec<LegacyPuzzleStatistics>>,
+    player_settings: LegacyPlayerSettings,
+}
+
+impl From<LegacyPuzzleStatistics> for PuzzleStatisticsV1 {
+    fn from(legacy: LegacyPuzzleStatistics) -> Self {
+        Self {
+            finish_time: legacy.finish_time,
+            duration: legacy.duration,
+            move_count: legacy.move_count,
+            // Every move was counted on its own and there were no hints or replays
+            multi_tile_move_count: 0,
+            hints_used: 0,
+            replay_id: None,
+        }
+    }
+}
+
+// Undo came later, until then every move counted
+impl From<LegacyPlayer> for PlayerV1 {
+    fn from(legacy: LegacyPlayer) -> Self {
+        let side = legacy.player_settings.num_rows_cols;
+        Self {
+            id: legacy.id,
+            username: legacy.username,
+            completed_puzzles: legacy
+                .completed_puzzles
+                .into_iter()
+                .map(|(num, stats)| (num, stats.into_iter().map(PuzzleStatisticsV1::from).collect()))
+                .collect(),
+            player_settings: PlayerSettingsV1 {
+                num_rows: side,
+                num_cols: side,
+                undo_counts_as_move: true,
+                auto_solve_speed: DEFAULT_AUTO_SOLVE_SPEED,
+            },
+        }
+    }
+}
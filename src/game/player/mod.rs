@@ -1,61 +1,358 @@
-use std::{collections::BTreeMap, sync::Mutex, time::Duration};
+use std::{collections::BTreeMap, fs, path::PathBuf, sync::Mutex, time::Duration};
 
 use chrono::{DateTime, Local};
-use ggez::{Context, GameResult};
+use directories::ProjectDirs;
+use ggez::{GameError, GameResult};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::game::{
+    puzzle::replay::Replay,
+    resources::image_loader::{ImageFit, ImageLoader, PuzzleId},
+    ui::error_message,
+};
+
+pub mod edit_profile_scene;
+mod legacy;
+pub mod profile_file;
+pub mod profile_scene;
+pub mod profiles_scene;
+pub mod save_error_scene;
+pub mod save_file;
 pub mod settings_scene;
+pub mod stats_scene;
 
 use lazy_static::lazy_static;
 
 // TODO use a parking lot Mutex
 lazy_static! {
     pub static ref PLAYER: Mutex<Option<Player>> = Mutex::new(None);
+    // Set from the command line, otherwise saves go in the user data dir
+    static ref SAVE_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+}
+
+pub fn set_save_dir(dir: PathBuf) { *SAVE_DIR.lock().unwrap() = Some(dir); }
+
+// Where player.dat and the replays are kept. This doesn't go through ggez's
+// filesystem so the headless commands can use it without a window.
+pub fn save_dir() -> PathBuf {
+    if let Some(dir) = SAVE_DIR.lock().unwrap().as_ref() {
+        return dir.clone();
+    }
+    // Same directory ggez uses for user data
+    ProjectDirs::from("", "cdknight", "SlidingPuzzle").map(|dirs| dirs.data_local_dir().to_path_buf()).unwrap_or_default()
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+// Plays moves as fast as a player pressing keys
+pub const DEFAULT_AUTO_SOLVE_SPEED: u32 = 4;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct PlayerSettings {
-    pub num_rows_cols: usize,
+    pub num_rows: usize,
+    pub num_cols: usize,
+    // Otherwise an undo takes back the move it undoes
+    pub undo_counts_as_move: bool,
+    // How fast auto-solve plays moves, 1 is the slowest
+    pub auto_solve_speed: u32,
+    pub image_fit: ImageFit,
+}
+
+impl PlayerSettings {
+    pub fn num_rows_cols(&self) -> (usize, usize) { (self.num_rows, self.num_cols) }
+}
+
+// How a game was played, so results that can't be compared aren't mixed up
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameMode {
+    Singleplayer,
+    // Singleplayer with at least one hint
+    Hinted,
+    MultiplayerWin,
+    MultiplayerLoss,
+}
+
+impl GameMode {
+    pub fn is_multiplayer(&self) -> bool { matches!(self, GameMode::MultiplayerWin | GameMode::MultiplayerLoss) }
+}
+
+impl std::fmt::Display for GameMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            GameMode::Singleplayer => "singleplayer",
+            GameMode::Hinted => "hinted",
+            GameMode::MultiplayerWin => "multiplayer win",
+            GameMode::MultiplayerLoss => "multiplayer loss",
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct PuzzleStatistics {
     pub finish_time: DateTime<Local>,
     pub duration: Duration,
+    // Every tile that slid counts as a move
     pub move_count: u32,
+    // A run of tiles sliding together counts as one move
+    pub multi_tile_move_count: u32,
+    pub hints_used: u32,
+    // Games that were saved before replays existed don't have one
+    pub replay_id: Option<Uuid>,
+    // Rows and columns. Unknown for old games that don't have a replay.
+    pub grid: Option<(usize, usize)>,
+    pub mode: GameMode,
+    // `slidingpuzzle scramble --seed` gives the same board. None for custom
+    // boards and old games.
+    pub scramble_seed: Option<u64>,
+    // The other player's username in multiplayer games
+    pub opponent: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl PuzzleStatistics {
+    // e.g. 4x4, or ? if it wasn't recorded
+    pub fn grid_name(&self) -> String { self.grid.map_or("?".to_string(), |(rows, cols)| format!("{}x{}", rows, cols)) }
+
+    // Goes after a game's moves and time in lists, e.g. ", won against Alex"
+    pub fn mode_note(&self) -> String {
+        let opponent = self.opponent.clone().unwrap_or_default();
+        match self.mode {
+            GameMode::Singleplayer => String::new(),
+            GameMode::Hinted => format!(", {} hint{}", self.hints_used, if self.hints_used == 1 { "" } else { "s" }),
+            GameMode::MultiplayerWin => format!(", won against {}", opponent),
+            GameMode::MultiplayerLoss => format!(", lost to {}", opponent),
+        }
+    }
+}
+
+// e.g. 2:05
+pub fn format_duration(duration: Duration) -> String { format!("{}:{:02}", duration.as_secs() / 60, duration.as_secs() % 60) }
+
+// Summed up from the games a puzzle was completed in
+#[derive(Clone, Copy, Debug)]
+pub struct PersonalBests {
+    pub completions: usize,
+    pub best_time: Duration,
+    pub best_moves: u32,
+    pub average_time: Duration,
+    pub average_moves: f32,
+}
+
+impl PersonalBests {
+    // None if there are no games
+    pub fn new<'a>(stats: impl IntoIterator<Item = &'a PuzzleStatistics>) -> Option<Self> {
+        let stats: Vec<&PuzzleStatistics> = stats.into_iter().collect();
+        let completions = stats.len();
+        Some(Self {
+            completions,
+            best_time: stats.iter().map(|stat| stat.duration).min()?,
+            best_moves: stats.iter().map(|stat| stat.move_count).min()?,
+            average_time: stats.iter().map(|stat| stat.duration).sum::<Duration>() / completions as u32,
+            average_moves: stats.iter().map(|stat| stat.move_count as f32).sum::<f32>() / completions as f32,
+        })
+    }
+}
+
+// Where to go once the profiles are loaded
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Startup {
+    // There aren't any profiles, a new player has to make one
+    #[default]
+    Intro,
+    // There's more than one, the last one played is already loaded
+    PickProfile,
+    Ready,
+}
+
+// A save that couldn't be read, moved out of the way so the next save can't
+// write over it
+pub struct BadSave {
+    pub path: PathBuf,
+    pub reason: String,
+    // Where it was moved to, None if it couldn't be
+    pub quarantined: Option<PathBuf>,
+    // The save before it, if that one reads
+    pub backup: Option<Player>,
+}
+
+// Changing Player, PlayerSettings or PuzzleStatistics changes the save
+// format, see save_file.rs
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Player {
     id: Uuid,
     username: String,
-    pub completed_puzzles: BTreeMap<usize, Vec<PuzzleStatistics>>,
+    pub completed_puzzles: BTreeMap<PuzzleId, Vec<PuzzleStatistics>>,
+    // How many times the game solved each puzzle. They aren't games the
+    // player finished, so they're only counted.
+    pub auto_solves: BTreeMap<PuzzleId, u32>,
+    // How many games of each puzzle were started, finished or not. Auto-solve
+    // demos aren't counted.
+    pub attempts: BTreeMap<PuzzleId, u32>,
     pub player_settings: PlayerSettings,
 }
 
 impl Player {
     pub fn username(&self) -> String { self.username.clone() }
-    pub fn load(ctx: &mut Context) -> GameResult<Self> {
-        let save_file = ctx.fs.open("/player.dat")?;
-        bincode::deserialize_from(save_file).map_err(|_| ggez::GameError::FilesystemError("Failed to read player.dat".to_string()))
+    // Only singleplayer games without hints count, at the grid size the player
+    // is playing at now
+    pub fn personal_bests(&self, puzzle_id: &PuzzleId) -> Option<PersonalBests> {
+        let grid = self.player_settings.num_rows_cols();
+        PersonalBests::new(
+            self.completed_puzzles.get(puzzle_id)?.iter().filter(|stat| stat.mode == GameMode::Singleplayer && stat.grid == Some(grid)),
+        )
+    }
+    // Adds a finished game and saves
+    pub fn record(&mut self, puzzle_id: &PuzzleId, stat: PuzzleStatistics) -> GameResult {
+        self.completed_puzzles.entry(puzzle_id.clone()).or_default().push(stat);
+        self.save()
     }
-    pub fn save(&self, ctx: &mut Context) -> GameResult {
-        let save_file = ctx.fs.create("/player.dat")?;
-        bincode::serialize_into(save_file, self).map_err(|_| ggez::GameError::FilesystemError("Failed to save player.dat".to_string()))
+    // Saves as the game starts, so games that are cancelled or never finish count
+    pub fn record_attempt(&mut self, puzzle_id: &PuzzleId) -> GameResult {
+        *self.attempts.entry(puzzle_id.clone()).or_default() += 1;
+        self.save()
     }
+    pub fn record_auto_solve(&mut self, puzzle_id: &PuzzleId) -> GameResult {
+        *self.auto_solves.entry(puzzle_id.clone()).or_default() += 1;
+        self.save()
+    }
+    pub fn id(&self) -> Uuid { self.id }
+    pub fn games_played(&self) -> usize { self.completed_puzzles.values().map(Vec::len).sum() }
+    pub fn save(&self) -> GameResult { save_file::write(&save_file::profile_path(self.id), self) }
     pub fn new(username: String, player_settings: PlayerSettings) -> Self {
-        Self { id: Uuid::new_v4(), username, completed_puzzles: BTreeMap::new(), player_settings }
+        Self {
+            id: Uuid::new_v4(),
+            username,
+            completed_puzzles: BTreeMap::new(),
+            auto_solves: BTreeMap::new(),
+            attempts: BTreeMap::new(),
+            player_settings,
+        }
+    }
+
+    // Moves player.dat to where profiles go now. Run once before the profiles
+    // are first loaded, it does nothing once player.dat has been moved.
+    pub fn migrate_single_profile() -> GameResult {
+        let old_path = save_file::single_profile_path();
+        if !old_path.is_file() {
+            return Ok(());
+        }
+        let player = save_file::read(&old_path)?;
+        let new_path = save_file::profile_path(player.id);
+        fs::create_dir_all(save_file::profiles_dir())?;
+        if save_file::backup_path(&old_path).is_file() {
+            fs::rename(save_file::backup_path(&old_path), save_file::backup_path(&new_path))?;
+        }
+        fs::rename(&old_path, &new_path)?;
+        Self::remember_last(player.id);
+        Ok(())
+    }
+
+    // Every profile, by username. Ones that can't be read are left out.
+    pub fn profiles() -> Vec<Player> {
+        let Ok(entries) = fs::read_dir(save_file::profiles_dir()) else { return vec![] };
+        let mut profiles: Vec<Player> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "dat"))
+            .filter_map(|path| save_file::read(&path).map_err(|e| log::warn!("{}", e)).ok())
+            .collect();
+        profiles.sort_by_key(|player| player.username.to_lowercase());
+        profiles
+    }
+
+    pub fn load_profile(id: Uuid) -> GameResult<Self> { save_file::read(&save_file::profile_path(id)) }
+
+    // The profile played last, or the first one
+    fn pick_last(mut profiles: Vec<Player>) -> Option<Player> {
+        let last = fs::read_to_string(save_file::last_profile_path()).ok().and_then(|id| id.trim().parse::<Uuid>().ok());
+        let idx = profiles.iter().position(|player| Some(player.id) == last).unwrap_or(0);
+        (!profiles.is_empty()).then(|| profiles.swap_remove(idx))
+    }
+
+    pub fn load() -> GameResult<Self> {
+        Self::pick_last(Self::profiles()).ok_or_else(|| GameError::FilesystemError("There are no profiles".to_string()))
+    }
+
+    // Matched case insensitively
+    pub fn load_by_username(username: &str) -> GameResult<Self> {
+        Self::profiles()
+            .into_iter()
+            .find(|player| player.username.eq_ignore_ascii_case(username))
+            .ok_or_else(|| GameError::FilesystemError(format!("There's no profile called {}", username)))
+    }
+
+    pub fn username_taken(username: &str, except: Option<Uuid>) -> bool {
+        Self::profiles().iter().any(|player| player.username.eq_ignore_ascii_case(username) && Some(player.id) != except)
+    }
+
+    fn remember_last(id: Uuid) {
+        let saved = fs::create_dir_all(save_file::profiles_dir()).and_then(|_| fs::write(save_file::last_profile_path(), id.to_string()));
+        if let Err(e) = saved {
+            log::warn!("Couldn't remember the last profile: {}", e);
+        }
+    }
+
+    // Makes this the profile being played, and the one picked next time
+    pub fn play_as(player: Player) {
+        ImageLoader::set_fit(player.player_settings.image_fit);
+        Self::remember_last(player.id);
+        *PLAYER.lock().unwrap() = Some(player);
+    }
+
+    // Moves every save that's there but can't be read to quarantine/. Run before
+    // the profiles are loaded, otherwise a bad save is just left out and looks
+    // like it was never there.
+    pub fn quarantine_bad_saves() -> Vec<BadSave> {
+        let mut paths = vec![save_file::single_profile_path()];
+        if let Ok(entries) = fs::read_dir(save_file::profiles_dir()) {
+            paths.extend(
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "dat")),
+            );
+        }
+        paths
+            .into_iter()
+            .filter(|path| path.is_file())
+            .filter_map(|path| {
+                let e = save_file::read(&path).err()?;
+                let backup = save_file::read(&save_file::backup_path(&path)).ok();
+                let quarantined = save_file::quarantine(&path).map_err(|e| log::warn!("{}", e)).ok();
+                Some(BadSave { reason: error_message(&e), path, quarantined, backup })
+            })
+            .collect()
     }
-    pub fn startup(ctx: &mut Context) -> bool {
+
+    // Along with its replays
+    pub fn delete_profile(id: Uuid) -> GameResult {
+        let path = save_file::profile_path(id);
+        if let Ok(player) = save_file::read(&path) {
+            for replay_id in player.completed_puzzles.values().flatten().filter_map(|stat| stat.replay_id) {
+                Replay::delete(replay_id);
+            }
+        }
+        fs::remove_file(&path)?;
+        let _ = fs::remove_file(save_file::backup_path(&path));
+
         let mut opt_player = PLAYER.lock().unwrap();
-        let loaded_player = Player::load(ctx);
+        if opt_player.as_ref().is_some_and(|player| player.id == id) {
+            *opt_player = None;
+        }
+        Ok(())
+    }
 
-        match loaded_player {
-            Err(_) => true,
-            Ok(p) => {
-                *opt_player = Some(p);
-                false
+    pub fn startup() -> Startup {
+        if let Err(e) = Self::migrate_single_profile() {
+            log::warn!("Couldn't move player.dat to the profiles: {}", e);
+        }
+        let profiles = Self::profiles();
+        let count = profiles.len();
+        match Self::pick_last(profiles) {
+            None => Startup::Intro,
+            Some(player) => {
+                Self::play_as(player);
+                if count > 1 {
+                    Startup::PickProfile
+                } else {
+                    Startup::Ready
+                }
             }
         }
     }
@@ -0,0 +1,246 @@
+// Profiles as JSON or TOML, picked by the file's extension, for moving them
+// between computers and for reading or fixing them by hand. Replays aren't
+// part of the file.
+
+use std::{fs, path::Path};
+
+use ggez::{GameError, GameResult};
+use serde::{de::DeserializeSeed, Deserialize, Deserializer, Serialize};
+
+use crate::game::puzzle::replay::Replay;
+
+use super::{legacy::SavedAs, save_file::SAVE_VERSION, settings_scene::MIN_BOARD_SIDE, GameMode, Player};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileFormat {
+    Json,
+    Toml,
+}
+
+impl ProfileFormat {
+    pub fn from_path(path: &Path) -> GameResult<Self> {
+        match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase()).as_deref() {
+            Some("json") => Ok(Self::Json),
+            Some("toml") => Ok(Self::Toml),
+            _ => Err(GameError::CustomError(format!("{} needs to end in .json or .toml", path.display()))),
+        }
+    }
+}
+
+// Same version as player.dat, so a profile from a newer game is turned away
+// instead of being read wrong. The player is read as a plain JSON or TOML value
+// until the version is known.
+#[derive(Serialize, Deserialize)]
+struct ProfileFile<P = Player> {
+    version: u32,
+    player: P,
+}
+
+fn to_text(player: &Player, format: ProfileFormat) -> Result<String, String> {
+    let file = ProfileFile { version: SAVE_VERSION, player: player.clone() };
+    match format {
+        ProfileFormat::Json => serde_json::to_string_pretty(&file).map_err(|e| e.to_string()),
+        // Going through a Value puts plain values ahead of tables, which TOML needs
+        ProfileFormat::Toml => toml::Value::try_from(&file).and_then(|value| toml::to_string_pretty(&value)).map_err(|e| e.to_string()),
+    }
+}
+
+// A profile from an older game is migrated like an old save
+fn migrate<'de, P: Deserializer<'de>>(file: ProfileFile<P>) -> Result<Player, String> {
+    if file.version > SAVE_VERSION {
+        return Err(format!("it's a version {} profile from a newer game, this one reads up to version {}", file.version, SAVE_VERSION));
+    }
+    SavedAs(file.version).deserialize(file.player).map_err(|e| e.to_string())
+}
+
+fn from_text(text: &str, format: ProfileFormat) -> Result<Player, String> {
+    match format {
+        ProfileFormat::Json => migrate(serde_json::from_str::<ProfileFile<serde_json::Value>>(text).map_err(|e| e.to_string())?),
+        ProfileFormat::Toml => migrate(toml::from_str::<ProfileFile<toml::Value>>(text).map_err(|e| e.to_string())?),
+    }
+}
+
+pub fn export(player: &Player, path: &Path) -> GameResult {
+    let text = to_text(player, ProfileFormat::from_path(path)?)
+        .map_err(|e| GameError::CustomError(format!("Failed to write the profile: {}", e)))?;
+    fs::write(path, text).map_err(|e| GameError::FilesystemError(format!("Couldn't write {}: {}", path.display(), e)))
+}
+
+// Reads and checks a profile. It isn't saved yet. Games whose replay isn't on
+// this computer lose their link to it.
+pub fn import(path: &Path) -> GameResult<Player> {
+    let text = fs::read_to_string(path).map_err(|e| GameError::FilesystemError(format!("Couldn't read {}: {}", path.display(), e)))?;
+    let mut player = from_text(&text, ProfileFormat::from_path(path)?)
+        .map_err(|e| GameError::CustomError(format!("{} isn't a profile: {}", path.display(), e)))?;
+    validate(&player).map_err(|e| GameError::CustomError(format!("{} has a problem: {}", path.display(), e)))?;
+    // Importing a profile again replaces it, but two different ones can't share a name
+    if Player::username_taken(&player.username, Some(player.id)) {
+        return Err(GameError::CustomError(format!("There's already a different profile called {}", player.username)));
+    }
+    for stat in player.completed_puzzles.values_mut().flatten() {
+        stat.replay_id = stat.replay_id.filter(|replay_id| Replay::exists(*replay_id));
+    }
+    Ok(player)
+}
+
+// Anything the settings scene wouldn't have let through
+fn validate(player: &Player) -> Result<(), String> {
+    if player.username.trim().is_empty() {
+        return Err("the username is empty".to_string());
+    }
+    let settings = &player.player_settings;
+    if settings.num_rows < MIN_BOARD_SIDE || settings.num_cols < MIN_BOARD_SIDE {
+        return Err(format!("the board size {}x{} is too small", settings.num_rows, settings.num_cols));
+    }
+    if settings.auto_solve_speed == 0 {
+        return Err("the auto-solve speed has to be at least 1".to_string());
+    }
+    for (puzzle_id, stats) in player.completed_puzzles.iter() {
+        let attempts = player.attempts.get(puzzle_id).copied().unwrap_or(0);
+        if (stats.len() as u32) > attempts {
+            return Err(format!("{} has {} games finished but only {} started", puzzle_id, stats.len(), attempts));
+        }
+        for stat in stats {
+            if stat.grid.is_some_and(|(rows, cols)| rows < MIN_BOARD_SIDE || cols < MIN_BOARD_SIDE) {
+                return Err(format!("a game of {} has the board size {}", puzzle_id, stat.grid_name()));
+            }
+            if stat.mode.is_multiplayer() != stat.opponent.is_some() {
+                return Err(format!(
+                    "a game of {} is {} but {} an opponent",
+                    puzzle_id,
+                    stat.mode,
+                    if stat.opponent.is_some() { "has" } else { "doesn't have" }
+                ));
+            }
+            if stat.mode == GameMode::Hinted && stat.hints_used == 0 {
+                return Err(format!("a game of {} is hinted but no hints were used", puzzle_id));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{Local, TimeZone};
+
+    use crate::game::{
+        player::{PlayerSettings, PuzzleStatistics},
+        resources::image_loader::{ImageFit, PuzzleId},
+    };
+
+    use super::*;
+
+    // A version 3 export, from when auto-solves were saved as games
+    const V3_PROFILE: &str = r#"{
+        "version": 3,
+        "player": {
+            "id": "5f0c6f8e-3f4d-4e52-9a3c-2f1d0b7a9c11",
+            "username": "Ada",
+            "completed_puzzles": {
+                "mountains": [
+                    {
+                        "finish_time": "2023-01-02T03:04:05+00:00",
+                        "duration": { "secs": 61, "nanos": 0 },
+                        "move_count": 40,
+                        "multi_tile_move_count": 31,
+                        "hints_used": 1,
+                        "replay_id": null,
+                        "grid": [4, 4],
+                        "mode": "Hinted",
+                        "scramble_seed": 7,
+                        "opponent": null
+                    },
+                    {
+                        "finish_time": "2023-02-03T04:05:06+00:00",
+                        "duration": { "secs": 20, "nanos": 0 },
+                        "move_count": 50,
+                        "multi_tile_move_count": 50,
+                        "hints_used": 0,
+                        "replay_id": null,
+                        "grid": [4, 4],
+                        "mode": "AutoSolved",
+                        "scramble_seed": 8,
+                        "opponent": null
+                    }
+                ]
+            },
+            "player_settings": {
+                "num_rows": 4,
+                "num_cols": 4,
+                "undo_counts_as_move": true,
+                "auto_solve_speed": 4,
+                "image_fit": "Letterbox"
+            }
+        }
+    }"#;
+
+    fn player() -> Player {
+        let settings =
+            PlayerSettings { num_rows: 3, num_cols: 4, undo_counts_as_move: false, auto_solve_speed: 6, image_fit: ImageFit::Crop };
+        let mut player = Player::new("Grace".to_string(), settings);
+        let puzzle_id = PuzzleId("mountains".to_string());
+        player.completed_puzzles.insert(
+            puzzle_id.clone(),
+            vec![PuzzleStatistics {
+                finish_time: Local.with_ymd_and_hms(2023, 1, 2, 3, 4, 5).unwrap(),
+                duration: Duration::from_millis(61_500),
+                move_count: 40,
+                multi_tile_move_count: 31,
+                hints_used: 0,
+                replay_id: None,
+                grid: Some((3, 4)),
+                mode: GameMode::MultiplayerLoss,
+                scramble_seed: Some(42),
+                opponent: Some("Ada".to_string()),
+            }],
+        );
+        player.attempts.insert(puzzle_id, 2);
+        player
+    }
+
+    fn same(a: &Player, b: &Player) -> bool { bincode::serialize(a).unwrap() == bincode::serialize(b).unwrap() }
+
+    #[test]
+    fn exports_read_back_the_same() {
+        let player = player();
+        for format in [ProfileFormat::Json, ProfileFormat::Toml] {
+            let text = to_text(&player, format).unwrap();
+            assert!(same(&from_text(&text, format).unwrap(), &player), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn older_exports_are_migrated() {
+        let player = from_text(V3_PROFILE, ProfileFormat::Json).unwrap();
+        let puzzle_id = PuzzleId("mountains".to_string());
+        assert_eq!(player.username(), "Ada");
+        let stats = &player.completed_puzzles[&puzzle_id];
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].mode, GameMode::Hinted);
+        assert_eq!(player.auto_solves[&puzzle_id], 1);
+        assert_eq!(player.attempts[&puzzle_id], 1);
+        assert_eq!(player.player_settings.image_fit, ImageFit::Letterbox);
+        assert!(validate(&player).is_ok());
+    }
+
+    #[test]
+    fn newer_exports_are_turned_away() {
+        let newer = to_text(&player(), ProfileFormat::Json).unwrap().replacen(
+            &format!("\"version\": {}", SAVE_VERSION),
+            &format!("\"version\": {}", SAVE_VERSION + 1),
+            1,
+        );
+        assert!(from_text(&newer, ProfileFormat::Json).unwrap_err().contains("newer game"));
+    }
+
+    #[test]
+    fn more_games_finished_than_started_is_a_problem() {
+        let mut player = player();
+        assert!(validate(&player).is_ok());
+        player.attempts.clear();
+        assert!(validate(&player).is_err());
+    }
+}
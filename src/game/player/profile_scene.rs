@@ -0,0 +1,174 @@
+// Export the profile to a JSON or TOML file, or import one that was exported
+// before. Importing a profile that's already here replaces it, the profile it
+// replaces is kept in its .bak file.
+
+use std::path::{Path, PathBuf};
+
+use ggez::{graphics::Color, Context, GameResult};
+
+use crate::game::{
+    animation::DrawablePos,
+    drawable::Drawable,
+    gmenu::menu_item_list::{GameMenuItemList, NewGameMenuItemData, NewGameMenuItemDataVariant},
+    input::{mouse::MouseAction, InputAction},
+    resources::theme::Theme,
+    scene::Scene,
+    ui::{clipboard, error_message, error_scene::try_scene, uitext::UIText},
+};
+
+use super::{profile_file, profiles_scene::back_to_profiles, save_dir, settings_scene::SettingsScene, Player, PLAYER};
+
+// Indices into options
+const PATH_ITEM: usize = 0;
+const PASTE_ITEM: usize = 1;
+const EXPORT_ITEM: usize = 2;
+const IMPORT_ITEM: usize = 3;
+
+pub struct ProfileScene {
+    title_text: UIText,
+    options: GameMenuItemList,
+    // How the last export or import went
+    status_text: Option<UIText>,
+
+    back: bool,
+}
+
+impl ProfileScene {
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        let title_text = UIText::new("Export or Import Profile".to_string(), Theme::fg_color(), 78.0, DrawablePos { x: 90.0, y: 90.0 });
+        let t_sz = title_text.text.measure(ctx)?;
+
+        let options = GameMenuItemList::new(
+            ctx,
+            vec![
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::InputItem {
+                        prompt: "Profile File (.json or .toml)".to_string(),
+                        is_num: false,
+                        initial_value: save_dir().join("profile.json").display().to_string(),
+                    },
+                    next_page: None,
+                },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::TextItem { text: "Paste Path from Clipboard".to_string() },
+                    next_page: None,
+                },
+                NewGameMenuItemData { variant: NewGameMenuItemDataVariant::TextItem { text: "Export".to_string() }, next_page: None },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::TextItem { text: "Import Profile".to_string() },
+                    next_page: None,
+                },
+            ],
+            90.0,
+            t_sz.y + 140.0,
+            1100.0,
+            110.0,
+        )?;
+
+        Ok(Self { title_text, options, status_text: None, back: false })
+    }
+
+    fn show_status(&mut self, status: String, color: Color) {
+        let y = self.options.items[IMPORT_ITEM].pos.y + 150.0;
+        self.status_text = Some(UIText::new(status, color, 38.0, DrawablePos { x: 90.0, y }));
+    }
+
+    fn paste_path(&mut self) {
+        match clipboard::get_text() {
+            Ok(text) => {
+                // File managers put quotes around paths with spaces when they're copied
+                self.options.items[PATH_ITEM].set_input_value(text.trim().trim_matches('"').to_string());
+                self.status_text = None;
+            }
+            Err(_) => self.show_status("Couldn't read the clipboard".to_string(), Theme::error_color()),
+        }
+    }
+
+    fn export(&mut self, path: &Path) {
+        let result = match PLAYER.lock().unwrap().as_ref() {
+            Some(player) => profile_file::export(player, path),
+            None => return self.show_status("There's no profile to export yet".to_string(), Theme::error_color()),
+        };
+        match result {
+            Ok(()) => self.show_status(format!("Exported to {}", path.display()), Theme::fg_color()),
+            Err(e) => self.show_status(format!("Couldn't export the profile: {}", error_message(&e)), Theme::error_color()),
+        }
+    }
+
+    fn import(&mut self, path: &Path) {
+        let player = match profile_file::import(path) {
+            Ok(player) => player,
+            Err(e) => return self.show_status(format!("Couldn't import the profile: {}", error_message(&e)), Theme::error_color()),
+        };
+        let username = player.username();
+        let saved = player.save();
+        Player::play_as(player);
+        match saved {
+            Ok(()) => self.show_status(format!("Imported {}'s profile", username), Theme::fg_color()),
+            Err(e) => self
+                .show_status(format!("Imported {}'s profile but couldn't save it: {}", username, error_message(&e)), Theme::error_color()),
+        }
+    }
+
+    fn activate_selected(&mut self) {
+        let path = PathBuf::from(self.options.items[PATH_ITEM].get_input_value().unwrap().trim());
+        match self.options.selected() {
+            PASTE_ITEM => self.paste_path(),
+            EXPORT_ITEM | IMPORT_ITEM if path.as_os_str().is_empty() =>
+                self.show_status("Enter the profile file's path first".to_string(), Theme::error_color()),
+            EXPORT_ITEM => self.export(&path),
+            IMPORT_ITEM => self.import(&path),
+            _ => {}
+        }
+    }
+}
+
+impl Drawable for ProfileScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut ggez::graphics::Canvas) -> GameResult {
+        self.title_text.draw(ctx, canvas)?;
+        self.options.draw(ctx, canvas)?;
+        if let Some(status_text) = &mut self.status_text {
+            status_text.draw(ctx, canvas)?;
+        }
+        Ok(())
+    }
+}
+
+impl Scene for ProfileScene {
+    fn handle_input_event(&mut self, ctx: &mut Context, key_input: InputAction) {
+        match key_input {
+            InputAction::Cancel => self.back = true,
+            InputAction::Select => self.activate_selected(),
+            _ => {}
+        }
+        self.options.handle_input_event(ctx, key_input);
+    }
+
+    fn handle_mouse_event(&mut self, ctx: &mut Context, mouse_input: MouseAction) {
+        self.options.handle_mouse_event(ctx, mouse_input);
+        if let MouseAction::Click(point) = mouse_input {
+            if self.options.items[self.options.selected()].contains(point) {
+                self.activate_selected();
+            }
+        }
+    }
+
+    fn text_input_event(&mut self, ctx: &mut Context, c: char) { self.options.text_input_event(ctx, c); }
+
+    // Only fills in the path, importing replaces the profile so it waits to be asked
+    fn file_dropped_event(&mut self, _ctx: &mut Context, path: PathBuf) {
+        self.options.items[PATH_ITEM].set_input_value(path.display().to_string());
+        self.status_text = None;
+    }
+
+    fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
+        if self.back {
+            // Still the intro if nothing was imported
+            let intro = PLAYER.lock().unwrap().is_none();
+            Some(try_scene(ctx, "Couldn't Return to the Settings", move |ctx| SettingsScene::new(ctx, intro), back_to_profiles))
+        } else {
+            // None of the items have pages of their own
+            self.options.next_scene(ctx)
+        }
+    }
+}
@@ -0,0 +1,130 @@
+// Pick who's playing, or make a new profile
+//
+// Up/Down, Enter -> play as that profile
+// Right -> rename or delete the selected profile
+
+use ggez::{Context, GameResult};
+use uuid::Uuid;
+
+use crate::game::{
+    animation::DrawablePos,
+    drawable::Drawable,
+    gmenu::menu_item_list::{GameMenuItemList, NewGameMenuItemData, NewGameMenuItemDataVariant},
+    input::{mouse::MouseAction, InputAction},
+    resources::theme::Theme,
+    scene::Scene,
+    ui::{
+        error_scene::{back_to_main_menu, try_scene},
+        uitext::UIText,
+    },
+};
+
+use super::{edit_profile_scene::EditProfileScene, settings_scene::SettingsScene, Player, PLAYER};
+
+enum Next {
+    MainMenu,
+    NewProfile,
+    Edit(Uuid),
+}
+
+// Where the scenes opened from here go back to when they can't be made
+pub fn back_to_profiles(ctx: &mut Context) -> GameResult<Box<dyn Scene>> { Ok(Box::new(ProfilesScene::new(ctx)?)) }
+
+pub struct ProfilesScene {
+    title_text: UIText,
+    hint_text: UIText,
+    options: GameMenuItemList,
+    // By username, "New Profile" comes after them
+    profiles: Vec<Player>,
+
+    next: Option<Next>,
+}
+
+impl ProfilesScene {
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        let title_text = UIText::new("Who's Playing?".to_string(), Theme::fg_color(), 78.0, DrawablePos { x: 90.0, y: 90.0 });
+        let t_sz = title_text.text.measure(ctx)?;
+        let hint_text = UIText::new(
+            "Enter: Play   Right: Rename or Delete".to_string(),
+            Theme::fg_color(),
+            28.0,
+            DrawablePos { x: 90.0, y: 100.0 + t_sz.y },
+        );
+
+        let profiles = Player::profiles();
+        let current = PLAYER.lock().unwrap().as_ref().map(|player| player.id);
+        let mut items: Vec<NewGameMenuItemData> = profiles
+            .iter()
+            .map(|player| NewGameMenuItemData {
+                variant: NewGameMenuItemDataVariant::TextItem {
+                    text: format!(
+                        "{}{}   {} games",
+                        player.username,
+                        if Some(player.id) == current { " (playing)" } else { "" },
+                        player.games_played()
+                    ),
+                },
+                next_page: None,
+            })
+            .collect();
+        items.push(NewGameMenuItemData {
+            variant: NewGameMenuItemDataVariant::TextItem { text: "New Profile".to_string() },
+            next_page: None,
+        });
+        let options = GameMenuItemList::new(ctx, items, 90.0, t_sz.y + 170.0, 900.0, 80.0)?;
+
+        Ok(Self { title_text, hint_text, options, profiles, next: None })
+    }
+
+    fn activate_selected(&mut self) {
+        self.next = Some(match self.profiles.get(self.options.selected()) {
+            Some(player) => {
+                Player::play_as(player.clone());
+                Next::MainMenu
+            }
+            None => Next::NewProfile,
+        });
+    }
+}
+
+impl Drawable for ProfilesScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut ggez::graphics::Canvas) -> GameResult {
+        self.title_text.draw(ctx, canvas)?;
+        self.hint_text.draw(ctx, canvas)?;
+        self.options.draw(ctx, canvas)
+    }
+}
+
+impl Scene for ProfilesScene {
+    fn handle_input_event(&mut self, ctx: &mut Context, key_input: InputAction) {
+        match key_input {
+            // Only if someone's already playing
+            InputAction::Cancel if PLAYER.lock().unwrap().is_some() => self.next = Some(Next::MainMenu),
+            InputAction::Select => self.activate_selected(),
+            InputAction::Right =>
+                if let Some(player) = self.profiles.get(self.options.selected()) {
+                    self.next = Some(Next::Edit(player.id));
+                },
+            _ => {}
+        }
+        self.options.handle_input_event(ctx, key_input);
+    }
+
+    fn handle_mouse_event(&mut self, ctx: &mut Context, mouse_input: MouseAction) {
+        self.options.handle_mouse_event(ctx, mouse_input);
+        if let MouseAction::Click(point) = mouse_input {
+            if self.options.items[self.options.selected()].contains(point) {
+                self.activate_selected();
+            }
+        }
+    }
+
+    fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
+        match self.next.take()? {
+            Next::MainMenu => Some(back_to_main_menu(ctx)),
+            Next::NewProfile => Some(try_scene(ctx, "Couldn't Make a New Profile", |ctx| SettingsScene::new(ctx, true), back_to_profiles)),
+            Next::Edit(id) =>
+                Some(try_scene(ctx, "Couldn't Edit the Profile", move |ctx| EditProfileScene::new(ctx, id), back_to_profiles)),
+        }
+    }
+}
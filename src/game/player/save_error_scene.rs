@@ -0,0 +1,140 @@
+// Shown when the game starts if a save couldn't be read. The bad saves have
+// already been moved to quarantine/, this says where and offers to restore a
+// profile from the save before it.
+//
+// Up/Down, Enter -> restore that profile, or continue
+// Escape -> continue
+
+use ggez::{graphics::Color, Context, GameResult};
+
+use crate::game::{
+    animation::DrawablePos,
+    drawable::Drawable,
+    gmenu::menu_item_list::{GameMenuItemList, NewGameMenuItemData, NewGameMenuItemDataVariant},
+    input::{mouse::MouseAction, InputAction},
+    resources::theme::Theme,
+    scene::Scene,
+    ui::{
+        error_message,
+        error_scene::{back_to_main_menu, main_menu, try_scene},
+        uitext::UIText,
+    },
+};
+
+use super::{profiles_scene::ProfilesScene, settings_scene::SettingsScene, BadSave, Player, Startup};
+
+pub struct SaveErrorScene {
+    title_text: UIText,
+    problems_text: UIText,
+    options: GameMenuItemList,
+    // How the last restore went
+    status_text: Option<UIText>,
+    // The backups that can be restored, in the same order as the options
+    // before "Continue"
+    backups: Vec<Player>,
+
+    next: bool,
+}
+
+impl SaveErrorScene {
+    pub fn new(ctx: &mut Context, bad_saves: Vec<BadSave>) -> GameResult<Self> {
+        let title_text =
+            UIText::new("Some Saves Couldn't Be Read".to_string(), Theme::error_color(), 78.0, DrawablePos { x: 90.0, y: 90.0 });
+        let t_sz = title_text.text.measure(ctx)?;
+
+        let problems = bad_saves
+            .iter()
+            .map(|bad_save| {
+                let moved = match &bad_save.quarantined {
+                    Some(path) => format!("It was moved to {}", path.display()),
+                    None => format!("It couldn't be moved, {} is still where it was", bad_save.path.display()),
+                };
+                let backup = match &bad_save.backup {
+                    Some(backup) => format!("{}'s save before it can be restored", backup.username),
+                    None => "There's no save before it to restore".to_string(),
+                };
+                format!("{}\n{}\n{}", bad_save.reason, moved, backup)
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let problems_text = UIText::new(problems, Theme::fg_color(), 28.0, DrawablePos { x: 90.0, y: 100.0 + t_sz.y });
+        let p_sz = problems_text.text.measure(ctx)?;
+
+        // Restoring over a save that couldn't be moved would make the bad save the backup
+        let backups: Vec<Player> =
+            bad_saves.into_iter().filter(|bad_save| bad_save.quarantined.is_some()).filter_map(|bad_save| bad_save.backup).collect();
+        let mut items: Vec<NewGameMenuItemData> = backups
+            .iter()
+            .map(|backup| NewGameMenuItemData {
+                variant: NewGameMenuItemDataVariant::TextItem { text: format!("Restore {} from Backup", backup.username) },
+                next_page: None,
+            })
+            .collect();
+        items.push(NewGameMenuItemData { variant: NewGameMenuItemDataVariant::TextItem { text: "Continue".to_string() }, next_page: None });
+        let options = GameMenuItemList::new(ctx, items, 90.0, 140.0 + t_sz.y + p_sz.y, 1100.0, 80.0)?;
+
+        Ok(Self { title_text, problems_text, options, status_text: None, backups, next: false })
+    }
+
+    fn show_status(&mut self, status: String, color: Color) {
+        let y = self.options.items[self.options.items.len() - 1].pos.y + 120.0;
+        self.status_text = Some(UIText::new(status, color, 38.0, DrawablePos { x: 90.0, y }));
+    }
+
+    fn activate_selected(&mut self) {
+        let Some(backup) = self.backups.get(self.options.selected()) else {
+            self.next = true;
+            return;
+        };
+        let username = backup.username.clone();
+        match backup.save() {
+            Ok(()) => self.show_status(format!("Restored {}", username), Theme::fg_color()),
+            Err(e) => self.show_status(format!("Couldn't restore {}: {}", username, error_message(&e)), Theme::error_color()),
+        }
+    }
+}
+
+impl Drawable for SaveErrorScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut ggez::graphics::Canvas) -> GameResult {
+        self.title_text.draw(ctx, canvas)?;
+        self.problems_text.draw(ctx, canvas)?;
+        self.options.draw(ctx, canvas)?;
+        if let Some(status_text) = &mut self.status_text {
+            status_text.draw(ctx, canvas)?;
+        }
+        Ok(())
+    }
+}
+
+impl Scene for SaveErrorScene {
+    fn handle_input_event(&mut self, ctx: &mut Context, key_input: InputAction) {
+        match key_input {
+            InputAction::Cancel => self.next = true,
+            InputAction::Select => self.activate_selected(),
+            _ => {}
+        }
+        self.options.handle_input_event(ctx, key_input);
+    }
+
+    fn handle_mouse_event(&mut self, ctx: &mut Context, mouse_input: MouseAction) {
+        self.options.handle_mouse_event(ctx, mouse_input);
+        if let MouseAction::Click(point) = mouse_input {
+            if self.options.items[self.options.selected()].contains(point) {
+                self.activate_selected();
+            }
+        }
+    }
+
+    fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
+        if !self.next {
+            // None of the items have pages of their own
+            return self.options.next_scene(ctx);
+        }
+        // Loaded again, restoring may have added a profile
+        Some(match Player::startup() {
+            Startup::Intro => try_scene(ctx, "Couldn't Make a New Profile", |ctx| SettingsScene::new(ctx, true), main_menu),
+            Startup::PickProfile => try_scene(ctx, "Couldn't List the Profiles", ProfilesScene::new, main_menu),
+            Startup::Ready => back_to_main_menu(ctx),
+        })
+    }
+}
@@ -0,0 +1,424 @@
+// player.dat is a small header with the save's version, then the player in
+// bincode. bincode doesn't say what it saved, so the version is how an old
+// save gets read into the struct it was written from and migrated forward.
+//
+// Adding or changing a field in Player, PlayerSettings or PuzzleStatistics
+// needs a new version: copy the old shape into legacy.rs, add a From to the
+// version after it, bump SAVE_VERSION and add it to SavedAs.
+//
+// Each profile is saved in profiles/<id>.dat, player.dat is where the only
+// profile was saved before there could be more than one.
+//
+// Saves are written to a temporary file that's renamed over the save, so a
+// crash halfway through can't leave half a save. The save before it is kept
+// next to it, e.g. player.dat.bak.
+//
+// A save that can't be read is moved to quarantine/ rather than left where the
+// next save would write over it.
+
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use bincode::Options;
+use chrono::Local;
+use ggez::{GameError, GameResult};
+use uuid::Uuid;
+
+use crate::game::puzzle::replay::Replay;
+
+use super::{legacy::SavedAs, save_dir, Player};
+
+const MAGIC: &[u8; 4] = b"SPSV";
+pub const SAVE_VERSION: u32 = 5;
+
+pub fn profiles_dir() -> PathBuf { save_dir().join("profiles") }
+pub fn profile_path(id: Uuid) -> PathBuf { profiles_dir().join(format!("{}.dat", id)) }
+// Which profile to pick when the game starts
+pub fn last_profile_path() -> PathBuf { profiles_dir().join("last_profile") }
+pub fn single_profile_path() -> PathBuf { save_dir().join("player.dat") }
+// The save before the last one
+pub fn backup_path(path: &Path) -> PathBuf { path.with_extension("dat.bak") }
+pub fn quarantine_dir() -> PathBuf { save_dir().join("quarantine") }
+
+fn read_error(path: &Path, reason: impl std::fmt::Display) -> GameError {
+    GameError::FilesystemError(format!("Failed to read {}: {}", path.display(), reason))
+}
+
+// Older versions go through every migration after them
+fn decode(version: u32, data: &[u8]) -> bincode::Result<Player> {
+    // Nothing can come after the player, it would mean the version is wrong
+    let options = bincode::DefaultOptions::new().with_fixint_encoding().reject_trailing_bytes();
+    options.deserialize_seed(SavedAs(version), data)
+}
+
+// Games from before the grid size was saved can get it from their replay's
+// board, if the replay is still there
+fn fill_in_grids(player: &mut Player) {
+    for stat in player.completed_puzzles.values_mut().flatten().filter(|stat| stat.grid.is_none()) {
+        stat.grid =
+            stat.replay_id.and_then(|replay_id| Replay::load(replay_id).ok()).map(|replay| (replay.board.rows(), replay.board.cols()));
+    }
+}
+
+pub fn read(path: &Path) -> GameResult<Player> {
+    let save_data = fs::read(path)?;
+    let mut player = match save_data.strip_prefix(MAGIC) {
+        Some(rest) if rest.len() >= 4 => {
+            let (version, data) = rest.split_at(4);
+            decode(u32::from_le_bytes(version.try_into().unwrap()), data).map_err(|e| read_error(path, e))
+        }
+        Some(_) => Err(read_error(path, "the file is cut short")),
+        // Saves from before there was a header, the newest that reads is the one
+        None => (0..=SAVE_VERSION)
+            .rev()
+            .find_map(|version| decode(version, &save_data).ok())
+            .ok_or_else(|| read_error(path, "it isn't a save from any version of the game")),
+    }?;
+    fill_in_grids(&mut player);
+    Ok(player)
+}
+
+// Moves a bad save out of the way, with the time so it can't replace one
+// quarantined before. Its backup stays, it's what the save can be restored from.
+pub fn quarantine(path: &Path) -> GameResult<PathBuf> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let new_path = quarantine_dir().join(format!("{}.{}", file_name, Local::now().format("%Y%m%d-%H%M%S")));
+    fs::create_dir_all(quarantine_dir())?;
+    fs::rename(path, &new_path)
+        .map_err(|e| GameError::FilesystemError(format!("Couldn't move {} to {}: {}", path.display(), new_path.display(), e)))?;
+    Ok(new_path)
+}
+
+pub fn write(path: &Path, player: &Player) -> GameResult {
+    let save_error = |reason: &dyn std::fmt::Display| GameError::FilesystemError(format!("Failed to save {}: {}", path.display(), reason));
+    let mut save_data = MAGIC.to_vec();
+    save_data.extend(SAVE_VERSION.to_le_bytes());
+    save_data.extend(bincode::serialize(player).map_err(|e| save_error(&e))?);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension("dat.tmp");
+    {
+        let mut tmp_file = File::create(&tmp_path)?;
+        tmp_file.write_all(&save_data)?;
+        tmp_file.sync_all()?;
+    }
+    if path.is_file() {
+        fs::copy(path, backup_path(path))?;
+    }
+    fs::rename(&tmp_path, path).map_err(|e| save_error(&e))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{sync::Mutex, time::Duration};
+
+    use chrono::TimeZone;
+    use lazy_static::lazy_static;
+
+    use crate::game::{
+        player::{set_save_dir, GameMode, PlayerSettings, PuzzleStatistics, DEFAULT_AUTO_SOLVE_SPEED},
+        puzzle::tiles::Board,
+        resources::image_loader::{ImageFit, PuzzleId},
+    };
+
+    use super::*;
+
+    // The first release's save, as it wrote player.dat
+    mod first_release {
+        use std::{collections::BTreeMap, time::Duration};
+
+        use chrono::{DateTime, Local};
+        use serde::Serialize;
+        use uuid::Uuid;
+
+        #[derive(Serialize)]
+        pub struct PlayerSettings {
+            pub num_rows_cols: usize,
+        }
+
+        #[derive(Serialize)]
+        pub struct PuzzleStatistics {
+            pub finish_time: DateTime<Local>,
+            pub duration: Duration,
+            pub move_count: u32,
+        }
+
+        #[derive(Serialize)]
+        pub struct Player {
+            pub id: Uuid,
+            pub username: String,
+            pub completed_puzzles: BTreeMap<usize, Vec<PuzzleStatistics>>,
+            pub player_settings: PlayerSettings,
+        }
+    }
+
+    // Written by hand from the shapes in legacy.rs. Ids are one byte repeated,
+    // every game was finished on 2023-01-02 or 2023-02-03.
+    //
+    // Ada, puzzle 3: one 4x4 game with 2 hints
+    const V1_SAVE: &[u8] = &[
+        0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+        0x11, 0x11, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x41, 0x64, 0x61, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x32, 0x30, 0x32, 0x33, 0x2d, 0x30, 0x31, 0x2d, 0x30, 0x32, 0x54, 0x30, 0x33, 0x3a, 0x30, 0x34, 0x3a, 0x30, 0x35, 0x2b, 0x30,
+        0x30, 0x3a, 0x30, 0x30, 0x5f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x28, 0x00, 0x00, 0x00, 0x1f, 0x00,
+        0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x01, 0x04, 0x00, 0x00, 0x00,
+    ];
+
+    // Grace, mountains: two 3x5 games, the second has replay 33..
+    const V2_SAVE: &[u8] = &[
+        0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22,
+        0x22, 0x22, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x47, 0x72, 0x61, 0x63, 0x65, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6d, 0x6f, 0x75, 0x6e, 0x74, 0x61, 0x69, 0x6e, 0x73, 0x02, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x32, 0x30, 0x32, 0x33, 0x2d, 0x30, 0x31, 0x2d, 0x30, 0x32,
+        0x54, 0x30, 0x33, 0x3a, 0x30, 0x34, 0x3a, 0x30, 0x35, 0x2b, 0x30, 0x30, 0x3a, 0x30, 0x30, 0x3d, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x1e, 0x00, 0x00, 0x00, 0x16, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x32, 0x30, 0x32, 0x33, 0x2d, 0x30, 0x32, 0x2d, 0x30, 0x33, 0x54, 0x30, 0x34, 0x3a, 0x30, 0x35, 0x3a, 0x30,
+        0x36, 0x2b, 0x30, 0x30, 0x3a, 0x30, 0x30, 0x32, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1c, 0x00, 0x00,
+        0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x33, 0x33, 0x33, 0x33,
+        0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+    ];
+
+    // Linus, mountains: a singleplayer game, an auto-solve and a win against Ada
+    const V3_SAVE: &[u8] = &[
+        0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44,
+        0x44, 0x44, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4c, 0x69, 0x6e, 0x75, 0x73, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6d, 0x6f, 0x75, 0x6e, 0x74, 0x61, 0x69, 0x6e, 0x73, 0x03, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x32, 0x30, 0x32, 0x33, 0x2d, 0x30, 0x31, 0x2d, 0x30, 0x32,
+        0x54, 0x30, 0x33, 0x3a, 0x30, 0x34, 0x3a, 0x30, 0x35, 0x2b, 0x30, 0x30, 0x3a, 0x30, 0x30, 0x46, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x23, 0x00, 0x00, 0x00, 0x1e, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x04, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x07, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x32, 0x30, 0x32, 0x33, 0x2d, 0x30, 0x32, 0x2d, 0x30,
+        0x33, 0x54, 0x30, 0x34, 0x3a, 0x30, 0x35, 0x3a, 0x30, 0x36, 0x2b, 0x30, 0x30, 0x3a, 0x30, 0x30, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x32, 0x00, 0x00, 0x00, 0x32, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x04, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x08, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x32, 0x30, 0x32, 0x33, 0x2d, 0x30, 0x32, 0x2d,
+        0x30, 0x33, 0x54, 0x30, 0x34, 0x3a, 0x30, 0x35, 0x3a, 0x30, 0x36, 0x2b, 0x30, 0x30, 0x3a, 0x30, 0x30, 0x50, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2d, 0x00, 0x00, 0x00, 0x28, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x04,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x09, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x41, 0x64, 0x61, 0x04, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    lazy_static! {
+        // There's one save dir for the whole process, tests using it take turns
+        static ref SAVE_DIR_LOCK: Mutex<()> = Mutex::new(());
+    }
+
+    // An empty save dir that's deleted once the test is done with it. Tests
+    // that set PLAYER hold one too, for the same reason.
+    pub(crate) struct TempSaveDir {
+        dir: PathBuf,
+        _lock: std::sync::MutexGuard<'static, ()>,
+    }
+
+    impl TempSaveDir {
+        pub(crate) fn new() -> Self {
+            let lock = SAVE_DIR_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let dir = std::env::temp_dir().join(format!("slidingpuzzle-test-{}", Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            set_save_dir(dir.clone());
+            Self { dir, _lock: lock }
+        }
+    }
+
+    impl Drop for TempSaveDir {
+        fn drop(&mut self) { fs::remove_dir_all(&self.dir).ok(); }
+    }
+
+    fn with_header(version: u32, data: &[u8]) -> Vec<u8> {
+        let mut save_data = MAGIC.to_vec();
+        save_data.extend(version.to_le_bytes());
+        save_data.extend(data);
+        save_data
+    }
+
+    fn player() -> Player {
+        let settings =
+            PlayerSettings { num_rows: 3, num_cols: 4, undo_counts_as_move: false, auto_solve_speed: 6, image_fit: ImageFit::Letterbox };
+        let mut player = Player::new("Ada".to_string(), settings);
+        let puzzle_id = PuzzleId("mountains".to_string());
+        player.completed_puzzles.insert(
+            puzzle_id.clone(),
+            vec![PuzzleStatistics {
+                finish_time: Local.with_ymd_and_hms(2023, 1, 2, 3, 4, 5).unwrap(),
+                duration: Duration::from_millis(61_500),
+                move_count: 40,
+                multi_tile_move_count: 31,
+                hints_used: 0,
+                replay_id: None,
+                grid: Some((3, 4)),
+                mode: GameMode::MultiplayerWin,
+                scramble_seed: Some(42),
+                opponent: Some("Grace".to_string()),
+            }],
+        );
+        player.attempts.insert(puzzle_id.clone(), 3);
+        player.auto_solves.insert(puzzle_id, 1);
+        player
+    }
+
+    fn same(a: &Player, b: &Player) -> bool { bincode::serialize(a).unwrap() == bincode::serialize(b).unwrap() }
+
+    #[test]
+    fn saves_read_back_the_same() {
+        let _dir = TempSaveDir::new();
+        let player = player();
+        let path = profile_path(player.id());
+        write(&path, &player).unwrap();
+        assert!(same(&read(&path).unwrap(), &player));
+    }
+
+    // Ada, puzzles 0 and 2 on a 5x5 board. Written the way the first release
+    // saved, without a header.
+    fn write_first_release_save() -> Uuid {
+        let game = |move_count| first_release::PuzzleStatistics {
+            finish_time: Local.with_ymd_and_hms(2023, 1, 2, 3, 4, 5).unwrap(),
+            duration: Duration::from_secs(95),
+            move_count,
+        };
+        let saved = first_release::Player {
+            id: Uuid::from_bytes([0x55; 16]),
+            username: "Ada".to_string(),
+            completed_puzzles: [(0, vec![game(40), game(52)]), (2, vec![game(31)])].into_iter().collect(),
+            player_settings: first_release::PlayerSettings { num_rows_cols: 5 },
+        };
+        fs::write(single_profile_path(), bincode::serialize(&saved).unwrap()).unwrap();
+        saved.id
+    }
+
+    #[test]
+    fn reads_a_save_from_the_first_release() {
+        let _dir = TempSaveDir::new();
+        let id = write_first_release_save();
+        let player = read(&single_profile_path()).unwrap();
+
+        assert_eq!(player.username(), "Ada");
+        assert_eq!(player.id(), id);
+        let stats = &player.completed_puzzles[&PuzzleId("0".to_string())];
+        assert_eq!(stats.iter().map(|stat| stat.move_count).collect::<Vec<_>>(), [40, 52]);
+        assert_eq!(stats[0].mode, GameMode::Singleplayer);
+        assert_eq!((stats[0].multi_tile_move_count, stats[0].hints_used, stats[0].replay_id, stats[0].grid), (0, 0, None, None));
+        assert_eq!(stats[0].duration, Duration::from_secs(95));
+        assert_eq!(player.completed_puzzles[&PuzzleId("2".to_string())].len(), 1);
+        assert_eq!(player.attempts[&PuzzleId("0".to_string())], 2);
+        assert_eq!(player.player_settings.num_rows_cols(), (5, 5));
+        assert!(player.player_settings.undo_counts_as_move);
+        assert_eq!(player.player_settings.auto_solve_speed, DEFAULT_AUTO_SOLVE_SPEED);
+        assert_eq!(player.player_settings.image_fit, ImageFit::Crop);
+    }
+
+    #[test]
+    fn the_first_releases_save_becomes_a_profile() {
+        let _dir = TempSaveDir::new();
+        let id = write_first_release_save();
+        assert!(Player::quarantine_bad_saves().is_empty());
+        Player::migrate_single_profile().unwrap();
+
+        assert!(!single_profile_path().exists());
+        let player = Player::load().unwrap();
+        assert_eq!(player.id(), id);
+        assert_eq!(player.games_played(), 3);
+    }
+
+    #[test]
+    fn reads_a_version_1_save_without_a_header() {
+        let _dir = TempSaveDir::new();
+        fs::write(single_profile_path(), V1_SAVE).unwrap();
+        let player = read(&single_profile_path()).unwrap();
+
+        assert_eq!(player.username(), "Ada");
+        assert_eq!(player.id(), Uuid::from_bytes([0x11; 16]));
+        let stats = &player.completed_puzzles[&PuzzleId("3".to_string())];
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].mode, GameMode::Hinted);
+        assert_eq!((stats[0].move_count, stats[0].hints_used, stats[0].grid), (40, 2, None));
+        assert_eq!(stats[0].duration, Duration::from_secs(95));
+        assert_eq!(player.attempts[&PuzzleId("3".to_string())], 1);
+        assert_eq!(player.player_settings.num_rows_cols(), (4, 4));
+        assert_eq!(player.player_settings.image_fit, ImageFit::Crop);
+    }
+
+    #[test]
+    fn reads_a_version_2_save_and_gets_grids_from_replays() {
+        let _dir = TempSaveDir::new();
+        let puzzle_id = PuzzleId("mountains".to_string());
+        let replay = Replay { id: Uuid::from_bytes([0x33; 16]), ..Replay::new(puzzle_id.clone(), Board::new(3, 5, (2, 4))) };
+        replay.save().unwrap();
+        let path = profile_path(Uuid::from_bytes([0x22; 16]));
+        fs::create_dir_all(profiles_dir()).unwrap();
+        fs::write(&path, with_header(2, V2_SAVE)).unwrap();
+        let player = read(&path).unwrap();
+
+        assert_eq!(player.username(), "Grace");
+        let stats = &player.completed_puzzles[&puzzle_id];
+        assert_eq!(stats.iter().map(|stat| stat.mode).collect::<Vec<_>>(), [GameMode::Singleplayer, GameMode::Singleplayer]);
+        // Only the second game has a replay to get it from
+        assert_eq!(stats[0].grid, None);
+        assert_eq!(stats[1].grid, Some((3, 5)));
+        assert_eq!(stats[1].replay_id, Some(replay.id));
+        assert_eq!(player.attempts[&puzzle_id], 2);
+        assert_eq!(player.player_settings.image_fit, ImageFit::Letterbox);
+        assert!(!player.player_settings.undo_counts_as_move);
+    }
+
+    #[test]
+    fn version_3_auto_solves_become_a_count() {
+        let player = decode(3, V3_SAVE).unwrap();
+        let puzzle_id = PuzzleId("mountains".to_string());
+        let stats = &player.completed_puzzles[&puzzle_id];
+        assert_eq!(stats.iter().map(|stat| stat.mode).collect::<Vec<_>>(), [GameMode::Singleplayer, GameMode::MultiplayerWin]);
+        assert_eq!(stats[1].opponent.as_deref(), Some("Ada"));
+        assert_eq!(stats[1].scramble_seed, Some(9));
+        assert_eq!(player.auto_solves[&puzzle_id], 1);
+        assert_eq!(player.attempts[&puzzle_id], 2);
+    }
+
+    #[test]
+    fn reads_the_current_version_without_a_header() {
+        let _dir = TempSaveDir::new();
+        let player = player();
+        fs::write(single_profile_path(), bincode::serialize(&player).unwrap()).unwrap();
+        assert!(same(&read(&single_profile_path()).unwrap(), &player));
+    }
+
+    #[test]
+    fn bad_saves_arent_read() {
+        let _dir = TempSaveDir::new();
+        let path = single_profile_path();
+        let data = bincode::serialize(&player()).unwrap();
+
+        fs::write(&path, with_header(SAVE_VERSION + 1, &data)).unwrap();
+        assert!(crate::game::ui::error_message(&read(&path).unwrap_err()).contains("newer version"));
+        fs::write(&path, [MAGIC.as_slice(), &[1, 0]].concat()).unwrap();
+        assert!(crate::game::ui::error_message(&read(&path).unwrap_err()).contains("cut short"));
+        // The right version with something left over means it isn't that version
+        fs::write(&path, with_header(SAVE_VERSION, &[data.as_slice(), &[0]].concat())).unwrap();
+        assert!(read(&path).is_err());
+        fs::write(&path, b"not a save").unwrap();
+        assert!(read(&path).is_err());
+    }
+
+    #[test]
+    fn writing_keeps_the_last_save_as_a_backup() {
+        let _dir = TempSaveDir::new();
+        let mut player = player();
+        let path = profile_path(player.id());
+        write(&path, &player).unwrap();
+        assert!(!backup_path(&path).exists());
+
+        player.username = "Grace".to_string();
+        write(&path, &player).unwrap();
+        assert_eq!(read(&path).unwrap().username(), "Grace");
+        assert_eq!(read(&backup_path(&path)).unwrap().username(), "Ada");
+        // The temporary file was renamed over the save
+        assert!(!path.with_extension("dat.tmp").exists());
+    }
+}
@@ -9,18 +9,23 @@ use crate::game::{
         DrawablePos,
     },
     drawable::Drawable,
-    gmenu::{
-        game_menu::GameMenu,
-        main_menu::MainMenu,
-        menu_item_list::{GameMenuItemList, NewGameMenuItemData, NewGameMenuItemDataVariant},
-    },
-    input::InputAction,
-    resources::theme::Theme,
+    gmenu::menu_item_list::{GameMenuItemList, NewGameMenuItemData, NewGameMenuItemDataVariant},
+    input::{mouse::MouseAction, InputAction},
+    resources::{image_loader::ImageFit, theme::Theme},
     scene::Scene,
-    ui::uitext::UIText,
+    ui::{
+        error_message,
+        error_scene::{back_to_main_menu, try_scene},
+        uitext::UIText,
+    },
 };
 
-use super::{Player, PlayerSettings, PLAYER};
+use super::{profile_scene::ProfileScene, profiles_scene::ProfilesScene, Player, PlayerSettings, DEFAULT_AUTO_SOLVE_SPEED, PLAYER};
+
+// Where the scenes opened from the settings go back to when they can't be made
+pub fn back_to_settings(intro: bool) -> impl Fn(&mut Context) -> GameResult<Box<dyn Scene>> {
+    move |ctx| Ok(Box::new(SettingsScene::new(ctx, intro)?))
+}
 
 pub struct SettingsScene {
     intro: bool,
@@ -30,6 +35,12 @@ pub struct SettingsScene {
     welcome: Rc<RefCell<UIText>>,
     // Menu options
     options: Rc<RefCell<GameMenuItemList>>,
+    // Next to the options, Right/Left moves between them
+    profile_buttons: GameMenuItemList,
+    profile_focused: bool,
+    // Why the settings weren't saved
+    error_text: Option<UIText>,
+    back_to_profiles: bool,
 
     advance_scene: bool,
 
@@ -37,30 +48,78 @@ pub struct SettingsScene {
 }
 
 const INPUT_BOX_HEIGHT: f32 = 110.0;
+pub const MIN_BOARD_SIDE: usize = 2;
+
+// Either a single number for a square board or rows x cols, e.g. 3x5
+pub fn parse_board_size(value: &str) -> Option<(usize, usize)> {
+    let value = value.trim().to_lowercase();
+    let (rows, cols) = match value.split_once('x') {
+        Some((rows, cols)) => (rows.trim().parse().ok()?, cols.trim().parse().ok()?),
+        None => {
+            let n = value.parse().ok()?;
+            (n, n)
+        }
+    };
+    if rows >= MIN_BOARD_SIDE && cols >= MIN_BOARD_SIDE {
+        Some((rows, cols))
+    } else {
+        None
+    }
+}
+
+fn parse_yes_no(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "y" | "yes" => Some(true),
+        "n" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_image_fit(value: &str) -> Option<ImageFit> {
+    match value.trim().to_lowercase().as_str() {
+        "crop" => Some(ImageFit::Crop),
+        "letterbox" => Some(ImageFit::Letterbox),
+        _ => None,
+    }
+}
 
 impl SettingsScene {
-    pub fn save_configuration(&mut self, ctx: &mut Context) -> GameResult {
+    pub fn save_configuration(&mut self) -> GameResult {
         // Should be safe to unwrap here due to prior parsing
         let mut options = self.options.borrow_mut();
         let username = options.items[0].get_input_value().unwrap();
-        let num_rows_cols = options.items[1].get_input_value().unwrap().parse().unwrap();
-
-        let mut opt_player = PLAYER.lock().unwrap();
-        match &mut *opt_player {
-            None => *opt_player = Some(Player::new(username, PlayerSettings { num_rows_cols })),
-            Some(player) => {
-                (*player).username = username;
-                (*player).player_settings.num_rows_cols = num_rows_cols;
-            }
-        }
+        let (num_rows, num_cols) = parse_board_size(&options.items[1].get_input_value().unwrap()).unwrap();
+        let undo_counts_as_move = parse_yes_no(&options.items[2].get_input_value().unwrap()).unwrap();
+        let auto_solve_speed = options.items[3].get_input_value().unwrap().parse().unwrap();
+        let image_fit = parse_image_fit(&options.items[4].get_input_value().unwrap()).unwrap();
 
-        // Finish sittings iff player save worked
-        if let Ok(_) = opt_player.as_ref().unwrap().save(ctx) {
-            self.advance_scene = true;
+        let id = if self.intro { None } else { PLAYER.lock().unwrap().as_ref().map(|player| player.id) };
+        if Player::username_taken(&username, id) {
+            drop(options);
+            self.show_error(format!("There's already a profile called {}", username));
+            return Ok(());
         }
 
+        // The intro makes a new profile, otherwise the current one changes
+        let settings = PlayerSettings { num_rows, num_cols, undo_counts_as_move, auto_solve_speed, image_fit };
+        let player = match PLAYER.lock().unwrap().clone() {
+            Some(player) if !self.intro => Player { username, player_settings: settings, ..player },
+            _ => Player::new(username, settings),
+        };
+
+        // Finish sittings iff player save worked
+        player.save()?;
+        Player::play_as(player);
+        self.advance_scene = true;
         Ok(())
     }
+
+    fn show_error(&mut self, error: String) {
+        // Under the profile buttons, there's room there
+        let last_button = self.profile_buttons.items.last().unwrap();
+        let pos = DrawablePos { x: last_button.pos.x, y: last_button.pos.y + INPUT_BOX_HEIGHT + 40.0 };
+        self.error_text = Some(UIText::new(error, Theme::error_color(), 38.0, pos));
+    }
     pub fn new(ctx: &mut Context, intro: bool) -> GameResult<Self> {
         let greeting = Rc::new(RefCell::new(UIText::new("Hi!".to_string(), Theme::fg_color(), 58.8, DrawablePos { x: 90.0, y: 90.0 })));
         let g_sz = greeting.borrow().text.measure(ctx)?;
@@ -73,7 +132,8 @@ impl SettingsScene {
         )));
         let w_sz = welcome.borrow().text.measure(ctx)?;
 
-        let opt_player = PLAYER.lock().unwrap();
+        // A new profile starts from the defaults
+        let opt_player = if intro { None } else { PLAYER.lock().unwrap().clone() };
 
         let o_y = g_sz.y + if intro { w_sz.y } else { 0.0 } + 120.0;
         let options = Rc::new(RefCell::new(GameMenuItemList::new(
@@ -89,12 +149,45 @@ impl SettingsScene {
                 },
                 NewGameMenuItemData {
                     variant: NewGameMenuItemDataVariant::InputItem {
-                        prompt: "Board Size".to_string(),
+                        prompt: "Board Size (e.g. 4 or 3x5)".to_string(),
+                        is_num: false,
+                        initial_value: match opt_player.as_ref().map(|player| player.player_settings.num_rows_cols()) {
+                            Some((num_rows, num_cols)) if num_rows == num_cols => format!("{}", num_rows),
+                            Some((num_rows, num_cols)) => format!("{}x{}", num_rows, num_cols),
+                            None => "".to_string(),
+                        },
+                    },
+                    next_page: None,
+                },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::InputItem {
+                        prompt: "Undos Count as Moves (y/n)".to_string(),
+                        is_num: false,
+                        initial_value: match opt_player.as_ref() {
+                            Some(player) if !player.player_settings.undo_counts_as_move => "n".to_string(),
+                            _ => "y".to_string(),
+                        },
+                    },
+                    next_page: None,
+                },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::InputItem {
+                        prompt: "Auto-solve Speed".to_string(),
                         is_num: true,
-                        initial_value: if let Some(player) = opt_player.as_ref() {
-                            format!("{}", player.player_settings.num_rows_cols)
-                        } else {
-                            "".to_string()
+                        initial_value: format!(
+                            "{}",
+                            opt_player.as_ref().map_or(DEFAULT_AUTO_SOLVE_SPEED, |player| player.player_settings.auto_solve_speed)
+                        ),
+                    },
+                    next_page: None,
+                },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::InputItem {
+                        prompt: "Fit Images (crop/letterbox)".to_string(),
+                        is_num: false,
+                        initial_value: match opt_player.as_ref() {
+                            Some(player) if player.player_settings.image_fit == ImageFit::Letterbox => "letterbox".to_string(),
+                            _ => "crop".to_string(),
                         },
                     },
                     next_page: None,
@@ -106,6 +199,29 @@ impl SettingsScene {
             INPUT_BOX_HEIGHT,
         )?));
 
+        let mut profile_buttons = GameMenuItemList::new(
+            ctx,
+            vec![
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::TextItem { text: "Switch Profile".to_string() },
+                    next_page: Some(Box::new(move |c| {
+                        try_scene(c, "Couldn't List the Profiles", ProfilesScene::new, back_to_settings(intro))
+                    })),
+                },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::TextItem { text: "Export or Import Profile".to_string() },
+                    next_page: Some(Box::new(move |c| {
+                        try_scene(c, "Couldn't Open the Export and Import", ProfileScene::new, back_to_settings(intro))
+                    })),
+                },
+            ],
+            90.0 + w_sz.x + 60.0,
+            o_y,
+            650.0,
+            INPUT_BOX_HEIGHT,
+        )?;
+        profile_buttons.items[0].deselect();
+
         let enter_confirm = Rc::new(RefCell::new(UIText::new(
             "Press Enter to Confirm.".to_string(),
             Theme::fg_color(),
@@ -146,12 +262,37 @@ impl SettingsScene {
             enter_confirm,
             welcome,
             options,
+            profile_buttons,
+            profile_focused: false,
+            error_text: None,
+            back_to_profiles: false,
             advance_scene: false,
             main: UIText::new("Settings".to_string(), Theme::fg_color(), 58.8, DrawablePos { x: 90.0, y: 90.0 }),
         })
     }
 }
 
+impl SettingsScene {
+    fn focus_profile(&mut self, focused: bool) {
+        if focused == self.profile_focused {
+            return;
+        }
+        let mut options = self.options.borrow_mut();
+        let selected = options.selected();
+        let button = self.profile_buttons.selected();
+        if focused {
+            options.items[selected].deselect();
+            self.profile_buttons.items[button].select();
+        } else {
+            self.profile_buttons.items[button].deselect();
+            options.items[selected].select();
+        }
+        self.profile_focused = focused;
+    }
+
+    fn intro_finished(&self) -> bool { self.intro_animation.as_ref().is_none_or(|anim| anim.finished()) }
+}
+
 impl Drawable for SettingsScene {
     fn draw(&mut self, ctx: &mut ggez::Context, canvas: &mut ggez::graphics::Canvas) -> ggez::GameResult {
         if let Some(anim) = &mut self.intro_animation {
@@ -167,14 +308,39 @@ impl Drawable for SettingsScene {
             self.main.draw(ctx, canvas)?;
         }
         self.options.borrow_mut().draw(ctx, canvas)?;
+        if self.intro_finished() {
+            self.profile_buttons.draw(ctx, canvas)?;
+        }
         self.enter_confirm.borrow_mut().draw(ctx, canvas)?;
+        if let Some(error_text) = &mut self.error_text {
+            error_text.draw(ctx, canvas)?;
+        }
 
         Ok(())
     }
 }
 impl Scene for SettingsScene {
-    fn text_input_event(&mut self, ctx: &mut ggez::Context, c: char) { self.options.borrow_mut().text_input_event(ctx, c); }
+    fn text_input_event(&mut self, ctx: &mut ggez::Context, c: char) {
+        if !self.profile_focused {
+            self.options.borrow_mut().text_input_event(ctx, c);
+        }
+    }
     fn handle_input_event(&mut self, ctx: &mut ggez::Context, key_input: InputAction) {
+        if self.profile_focused {
+            match key_input {
+                InputAction::Left => self.focus_profile(false),
+                InputAction::Up | InputAction::Down | InputAction::Select => self.profile_buttons.handle_input_event(ctx, key_input),
+                _ => {}
+            }
+            return;
+        }
+        if let (InputAction::Right, true) = (&key_input, self.intro_finished()) {
+            return self.focus_profile(true);
+        }
+        // Someone making a new profile can go back to the ones there are
+        if let (InputAction::Cancel, true) = (&key_input, self.intro) {
+            self.back_to_profiles = !Player::profiles().is_empty();
+        }
         if let InputAction::Select = key_input {
             let mut valid_inputs = true;
             for option in &mut self.options.borrow_mut().items {
@@ -183,19 +349,46 @@ impl Scene for SettingsScene {
                     valid_inputs = false
                 }
             }
+            if parse_board_size(&self.options.borrow_mut().items[1].get_input_value().unwrap()).is_none() {
+                valid_inputs = false;
+            }
+            if parse_yes_no(&self.options.borrow_mut().items[2].get_input_value().unwrap()).is_none() {
+                valid_inputs = false;
+            }
+            if !matches!(self.options.borrow_mut().items[3].get_input_value().unwrap().parse::<u32>(), Ok(speed) if speed > 0) {
+                valid_inputs = false;
+            }
+            if parse_image_fit(&self.options.borrow_mut().items[4].get_input_value().unwrap()).is_none() {
+                valid_inputs = false;
+            }
             if valid_inputs {
-                self.save_configuration(ctx).expect("Failed to save configuration");
+                if let Err(e) = self.save_configuration() {
+                    self.show_error(format!("Couldn't save the profile: {}", error_message(&e)));
+                }
             }
         }
         // TODO make sure to handle this only if the opening animations have finished
         self.options.borrow_mut().handle_input_event(ctx, key_input);
     }
 
+    fn handle_mouse_event(&mut self, ctx: &mut ggez::Context, mouse_input: MouseAction) {
+        let (MouseAction::Hover(point) | MouseAction::Click(point)) = mouse_input else { return };
+        if self.intro_finished() && self.profile_buttons.items.iter().any(|item| item.contains(point)) {
+            self.focus_profile(true);
+            self.profile_buttons.handle_mouse_event(ctx, mouse_input);
+        } else if self.options.borrow().items.iter().any(|item| item.contains(point)) {
+            self.focus_profile(false);
+            self.options.borrow_mut().handle_mouse_event(ctx, mouse_input);
+        }
+    }
+
     fn next_scene(&mut self, ctx: &mut ggez::Context) -> Option<Box<dyn Scene>> {
         if self.advance_scene {
-            Some(Box::new(GameMenu::new::<MainMenu>(ctx).expect("Failed to create game menu")))
+            Some(back_to_main_menu(ctx))
+        } else if self.back_to_profiles {
+            Some(try_scene(ctx, "Couldn't List the Profiles", ProfilesScene::new, back_to_settings(true)))
         } else {
-            None
+            self.profile_buttons.next_scene(ctx)
         }
     }
 
@@ -0,0 +1,262 @@
+// Personal bests and recent games for each puzzle that's been played
+//
+// Left/Right -> the previous/next puzzle
+// Up/Down, Enter -> pick a game to watch its replay
+
+use std::cmp::Reverse;
+
+use ggez::{Context, GameResult};
+
+use crate::game::{
+    animation::DrawablePos,
+    drawable::Drawable,
+    gmenu::{
+        game_menu::GameMenu,
+        history_menu::{history, HistoryMenu},
+        menu_item_list::{GameMenuItemList, NewGameMenuItemData, NewGameMenuItemDataVariant},
+    },
+    input::{mouse::MouseAction, InputAction},
+    puzzle::{replay::Replay, replay_scene::ReplayScene},
+    resources::{
+        image_loader::{ImageLoader, PuzzleId},
+        theme::Theme,
+    },
+    scene::Scene,
+    ui::{
+        error_scene::{main_menu, try_scene},
+        uitext::UIText,
+    },
+};
+
+use super::{format_duration, GameMode, PersonalBests, Player, PuzzleStatistics, PLAYER};
+
+// Any more won't fit under the summary
+const MAX_LISTED_GAMES: usize = 4;
+const MAX_LISTED_GRIDS: usize = 3;
+
+// Bests for each grid size from singleplayer games without hints, then how
+// many games were finished, how the rest of them went and how often the game
+// solved it instead
+fn summary(stats: &[PuzzleStatistics], attempts: u32, auto_solves: u32) -> Vec<String> {
+    let mut grids: Vec<Option<(usize, usize)>> =
+        stats.iter().filter(|stat| stat.mode == GameMode::Singleplayer).map(|stat| stat.grid).collect();
+    // Unknown sizes last
+    grids.sort_by_key(|grid| (grid.is_none(), *grid));
+    grids.dedup();
+
+    let mut lines: Vec<String> = grids
+        .into_iter()
+        .take(MAX_LISTED_GRIDS)
+        .filter_map(|grid| {
+            let games: Vec<&PuzzleStatistics> =
+                stats.iter().filter(|stat| stat.mode == GameMode::Singleplayer && stat.grid == grid).collect();
+            let bests = PersonalBests::new(games.iter().copied())?;
+            Some(format!(
+                "{}: best {}, fewest moves {}, average {} and {:.1} moves over {} finished",
+                games[0].grid_name(),
+                format_duration(bests.best_time),
+                bests.best_moves,
+                format_duration(bests.average_time),
+                bests.average_moves,
+                bests.completions
+            ))
+        })
+        .collect();
+
+    lines.push(format!("Finished {} of {} games started", stats.len(), attempts));
+    let count = |mode: GameMode| stats.iter().filter(|stat| stat.mode == mode).count();
+    let (wins, losses) = (count(GameMode::MultiplayerWin), count(GameMode::MultiplayerLoss));
+    if wins + losses > 0 {
+        lines.push(format!("Multiplayer: {} won, {} lost", wins, losses));
+    }
+    let hinted = count(GameMode::Hinted);
+    if hinted > 0 {
+        lines.push(format!("Also {} with hints", hinted));
+    }
+    if auto_solves > 0 {
+        lines.push(format!("Auto-solved {} time{}", auto_solves, if auto_solves == 1 { "" } else { "s" }));
+    }
+    lines
+}
+
+// Every puzzle started, finished or auto-solved, the most recently finished
+// first and ones that were never finished last
+fn played_puzzles(player: &Player) -> Vec<PuzzleId> {
+    let mut puzzle_ids: Vec<&PuzzleId> =
+        player.completed_puzzles.keys().chain(player.attempts.keys()).chain(player.auto_solves.keys()).collect();
+    puzzle_ids.sort();
+    puzzle_ids.dedup();
+    let last_finished = |puzzle_id: &PuzzleId| player.completed_puzzles.get(puzzle_id)?.iter().map(|stat| stat.finish_time).max();
+    puzzle_ids.sort_by_key(|puzzle_id| Reverse(last_finished(puzzle_id)));
+    puzzle_ids.into_iter().cloned().collect()
+}
+
+pub struct StatsScene {
+    title_text: UIText,
+    nav_text: UIText,
+    summary_texts: Vec<UIText>,
+    // None if the puzzle has no games, the list needs at least one item
+    games: Option<GameMenuItemList>,
+
+    // See played_puzzles
+    puzzle_ids: Vec<PuzzleId>,
+    current: usize,
+    switch_to: Option<usize>,
+    back: bool,
+}
+
+impl StatsScene {
+    // The most recently completed puzzle if none is given
+    pub fn new(ctx: &mut Context, puzzle_id: Option<PuzzleId>) -> GameResult<Self> {
+        let opt_player = PLAYER.lock().unwrap();
+        // Player guaranteed to be some at this point
+        let player = opt_player.as_ref().unwrap();
+
+        let puzzle_ids = played_puzzles(player);
+        let current = puzzle_id.and_then(|puzzle_id| puzzle_ids.iter().position(|id| *id == puzzle_id)).unwrap_or(0);
+
+        let title_text = UIText::new(
+            puzzle_ids.get(current).map_or("Statistics".to_string(), ImageLoader::title),
+            Theme::fg_color(),
+            78.0,
+            DrawablePos { x: 90.0, y: 90.0 },
+        );
+        let t_sz = title_text.text.measure(ctx)?;
+        let nav_text = UIText::new(
+            if puzzle_ids.is_empty() {
+                "No puzzles played yet".to_string()
+            } else {
+                format!("Puzzle {} of {}   Left/Right: Other puzzles", current + 1, puzzle_ids.len())
+            },
+            Theme::fg_color(),
+            28.0,
+            DrawablePos { x: 90.0, y: 100.0 + t_sz.y },
+        );
+
+        let stats = puzzle_ids.get(current).and_then(|puzzle_id| player.completed_puzzles.get(puzzle_id)).cloned().unwrap_or_default();
+        let attempts = puzzle_ids.get(current).and_then(|puzzle_id| player.attempts.get(puzzle_id)).copied().unwrap_or(0);
+        let auto_solves = puzzle_ids.get(current).and_then(|puzzle_id| player.auto_solves.get(puzzle_id)).copied().unwrap_or(0);
+        let summary_y = 170.0 + t_sz.y;
+        let summary_texts: Vec<UIText> = summary(&stats, attempts, auto_solves)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, line)| UIText::new(line, Theme::fg_color(), 34.0, DrawablePos { x: 90.0, y: summary_y + idx as f32 * 50.0 }))
+            .collect();
+
+        let mut recent: Vec<_> = stats.into_iter().collect();
+        recent.sort_by_key(|stat| Reverse(stat.finish_time));
+        let game_items: Vec<NewGameMenuItemData> = recent
+            .into_iter()
+            .take(MAX_LISTED_GAMES)
+            .map(|stat| NewGameMenuItemData {
+                variant: NewGameMenuItemDataVariant::TextItem {
+                    text: format!(
+                        "{} ({}): {} moves in {}{}",
+                        stat.finish_time.format("%Y-%m-%d %H:%M"),
+                        stat.grid_name(),
+                        stat.move_count,
+                        format_duration(stat.duration),
+                        stat.mode_note()
+                    ),
+                },
+                // Games from before replays were recorded can't be watched
+                next_page: stat.replay_id.map(|replay_id| -> Box<dyn Fn(&mut Context) -> Box<dyn Scene>> {
+                    Box::new(move |c| {
+                        try_scene(c, "Couldn't Play the Replay", move |ctx| ReplayScene::new(ctx, Replay::load(replay_id)?), history)
+                    })
+                }),
+            })
+            .collect();
+        let games_y = summary_y + summary_texts.len() as f32 * 50.0 + 40.0;
+        let games = if game_items.is_empty() {
+            None
+        } else {
+            Some(GameMenuItemList::new(ctx, game_items, 90.0, games_y, 1000.0, 80.0)?)
+        };
+
+        Ok(Self { title_text, nav_text, summary_texts, games, puzzle_ids, current, switch_to: None, back: false })
+    }
+}
+
+impl Drawable for StatsScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut ggez::graphics::Canvas) -> GameResult {
+        self.title_text.draw(ctx, canvas)?;
+        self.nav_text.draw(ctx, canvas)?;
+        for summary_text in self.summary_texts.iter_mut() {
+            summary_text.draw(ctx, canvas)?;
+        }
+        if let Some(games) = &mut self.games {
+            games.draw(ctx, canvas)?;
+        }
+        Ok(())
+    }
+}
+
+impl Scene for StatsScene {
+    fn handle_input_event(&mut self, ctx: &mut Context, key_input: InputAction) {
+        let count = self.puzzle_ids.len();
+        match key_input {
+            InputAction::Cancel => self.back = true,
+            InputAction::Left if count > 1 => self.switch_to = Some((self.current + count - 1) % count),
+            InputAction::Right if count > 1 => self.switch_to = Some((self.current + 1) % count),
+            _ =>
+                if let Some(games) = &mut self.games {
+                    games.handle_input_event(ctx, key_input);
+                },
+        }
+    }
+
+    fn handle_mouse_event(&mut self, ctx: &mut Context, mouse_input: MouseAction) {
+        if let Some(games) = &mut self.games {
+            games.handle_mouse_event(ctx, mouse_input);
+        }
+    }
+
+    fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
+        if self.back {
+            Some(try_scene(ctx, "Couldn't Return to the History", GameMenu::new::<HistoryMenu>, main_menu))
+        } else if let Some(idx) = self.switch_to.take() {
+            let puzzle_id = self.puzzle_ids[idx].clone();
+            Some(try_scene(ctx, "Couldn't Show the Statistics", move |ctx| Self::new(ctx, Some(puzzle_id.clone())), history))
+        } else {
+            self.games.as_mut()?.next_scene(ctx)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{Local, TimeZone};
+
+    use crate::game::{player::PlayerSettings, resources::image_loader::ImageFit};
+
+    use super::*;
+
+    #[test]
+    fn puzzles_only_started_or_auto_solved_are_listed_last() {
+        let settings =
+            PlayerSettings { num_rows: 4, num_cols: 4, undo_counts_as_move: true, auto_solve_speed: 4, image_fit: ImageFit::Crop };
+        let mut player = Player::new("Ada".to_string(), settings);
+        let id = |name: &str| PuzzleId(name.to_string());
+        let finished = |day| PuzzleStatistics {
+            finish_time: Local.with_ymd_and_hms(2023, 1, day, 3, 4, 5).unwrap(),
+            duration: Duration::from_secs(30),
+            move_count: 20,
+            multi_tile_move_count: 18,
+            hints_used: 0,
+            replay_id: None,
+            grid: Some((4, 4)),
+            mode: GameMode::Singleplayer,
+            scramble_seed: None,
+            opponent: None,
+        };
+        player.completed_puzzles.insert(id("lake"), vec![finished(2)]);
+        player.completed_puzzles.insert(id("mountains"), vec![finished(1), finished(3)]);
+        player.attempts.extend([(id("lake"), 1), (id("mountains"), 2), (id("forest"), 1)]);
+        player.auto_solves.insert(id("desert"), 1);
+
+        assert_eq!(played_puzzles(&player), [id("mountains"), id("lake"), id("desert"), id("forest")]);
+    }
+}
@@ -0,0 +1,170 @@
+// Play a board typed or pasted in text notation, e.g. "1,2,3/4,0,5/7,8,6"
+
+use ggez::{Context, GameResult};
+
+use crate::game::{
+    animation::DrawablePos,
+    drawable::Drawable,
+    gmenu::menu_item_list::{GameMenuItemList, NewGameMenuItemData, NewGameMenuItemDataVariant},
+    input::{mouse::MouseAction, InputAction},
+    resources::{
+        image_loader::{ImageLoader, PuzzleId},
+        theme::Theme,
+    },
+    scene::Scene,
+    ui::{
+        clipboard,
+        error_scene::{back_to_main_menu, try_scene},
+        uitext::UIText,
+    },
+};
+
+use super::tiles::{Board, TileState};
+
+// Indices into options
+const BOARD_ITEM: usize = 0;
+const PUZZLE_ITEM: usize = 1;
+const PASTE_ITEM: usize = 2;
+const PLAY_ITEM: usize = 3;
+
+pub struct CustomBoardScene {
+    title_text: UIText,
+    options: GameMenuItemList,
+    // Why the board can't be played
+    error_text: Option<UIText>,
+
+    start_game: Option<(PuzzleId, Board)>,
+    back: bool,
+}
+
+impl CustomBoardScene {
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        let title_text = UIText::new("Custom Board".to_string(), Theme::fg_color(), 78.0, DrawablePos { x: 90.0, y: 90.0 });
+        let t_sz = title_text.text.measure(ctx)?;
+
+        let options = GameMenuItemList::new(
+            ctx,
+            vec![
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::InputItem {
+                        prompt: "Board (e.g. 1,2,3/4,0,5/7,8,6)".to_string(),
+                        is_num: false,
+                        initial_value: "".to_string(),
+                    },
+                    next_page: None,
+                },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::InputItem {
+                        prompt: "Puzzle".to_string(),
+                        is_num: false,
+                        initial_value: ImageLoader::id_at(0).unwrap_or_default().0,
+                    },
+                    next_page: None,
+                },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::TextItem { text: "Paste Board from Clipboard".to_string() },
+                    next_page: None,
+                },
+                NewGameMenuItemData { variant: NewGameMenuItemDataVariant::TextItem { text: "Play".to_string() }, next_page: None },
+            ],
+            90.0,
+            t_sz.y + 140.0,
+            900.0,
+            110.0,
+        )?;
+
+        Ok(Self { title_text, options, error_text: None, start_game: None, back: false })
+    }
+
+    fn show_error(&mut self, error: String) {
+        let y = self.options.items[PLAY_ITEM].pos.y + 150.0;
+        self.error_text = Some(UIText::new(error, Theme::error_color(), 38.0, DrawablePos { x: 90.0, y }));
+    }
+
+    fn paste_board(&mut self) {
+        match clipboard::get_text() {
+            Ok(text) => {
+                self.options.items[BOARD_ITEM].set_input_value(text.trim().to_string());
+                self.error_text = None;
+            }
+            Err(_) => self.show_error("Couldn't read the clipboard".to_string()),
+        }
+    }
+
+    fn play(&mut self) {
+        let notation = self.options.items[BOARD_ITEM].get_input_value().unwrap();
+        let board: Board = match notation.parse() {
+            Ok(board) => board,
+            Err(e) => return self.show_error(e.to_string()),
+        };
+        if !board.is_solvable() {
+            return self.show_error("That board can't be solved".to_string());
+        }
+
+        let puzzle_id = PuzzleId(self.options.items[PUZZLE_ITEM].get_input_value().unwrap().trim().to_string());
+        if !ImageLoader::has_img(&puzzle_id) {
+            return self.show_error(format!("There is no puzzle {}", puzzle_id));
+        }
+        self.start_game = Some((puzzle_id, board));
+    }
+
+    fn activate_selected(&mut self) {
+        match self.options.selected() {
+            PASTE_ITEM => self.paste_board(),
+            PLAY_ITEM => self.play(),
+            _ => {}
+        }
+    }
+}
+
+impl Drawable for CustomBoardScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut ggez::graphics::Canvas) -> GameResult {
+        self.title_text.draw(ctx, canvas)?;
+        self.options.draw(ctx, canvas)?;
+        if let Some(error_text) = &mut self.error_text {
+            error_text.draw(ctx, canvas)?;
+        }
+        Ok(())
+    }
+}
+
+impl Scene for CustomBoardScene {
+    fn handle_input_event(&mut self, ctx: &mut Context, key_input: InputAction) {
+        match key_input {
+            InputAction::Cancel => self.back = true,
+            InputAction::Select => self.activate_selected(),
+            _ => {}
+        }
+        self.options.handle_input_event(ctx, key_input);
+    }
+
+    fn handle_mouse_event(&mut self, ctx: &mut Context, mouse_input: MouseAction) {
+        self.options.handle_mouse_event(ctx, mouse_input);
+        if let MouseAction::Click(point) = mouse_input {
+            if self.options.items[self.options.selected()].contains(point) {
+                self.activate_selected();
+            }
+        }
+    }
+
+    fn text_input_event(&mut self, ctx: &mut Context, c: char) { self.options.text_input_event(ctx, c); }
+
+    fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
+        if let Some((puzzle_id, board)) = self.start_game.take() {
+            Some(try_scene(
+                ctx,
+                "Couldn't Start the Game",
+                move |ctx| {
+                    let pos = TileState::center_xy(ctx);
+                    TileState::new_custom(ctx, &puzzle_id, &board, pos)
+                },
+                |ctx| Ok(Box::new(CustomBoardScene::new(ctx)?)),
+            ))
+        } else if self.back {
+            Some(back_to_main_menu(ctx))
+        } else {
+            // None of the items have pages of their own
+            self.options.next_scene(ctx)
+        }
+    }
+}
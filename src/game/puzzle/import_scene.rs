@@ -0,0 +1,163 @@
+// Add an image of the player's own as a puzzle, by typing or pasting its path
+// or dragging it onto the window
+
+use std::path::{Path, PathBuf};
+
+use ggez::{Context, GameResult};
+
+use crate::game::{
+    animation::DrawablePos,
+    drawable::Drawable,
+    gmenu::menu_item_list::{GameMenuItemList, NewGameMenuItemData, NewGameMenuItemDataVariant},
+    input::{mouse::MouseAction, InputAction},
+    resources::{
+        image_loader::{ImageLoader, PuzzleId},
+        theme::Theme,
+    },
+    scene::Scene,
+    ui::{
+        clipboard, error_message,
+        error_scene::{back_to_main_menu, main_menu, try_scene},
+        uitext::UIText,
+    },
+};
+
+use super::puzzle_view::PuzzleView;
+
+// Indices into options
+const PATH_ITEM: usize = 0;
+const PASTE_ITEM: usize = 1;
+const IMPORT_ITEM: usize = 2;
+
+pub struct ImportScene {
+    title_text: UIText,
+    options: GameMenuItemList,
+    // Why the image couldn't be imported
+    error_text: Option<UIText>,
+
+    imported: Option<PuzzleId>,
+    back: bool,
+}
+
+impl ImportScene {
+    // dropped is a file that was dragged onto the window, which is imported straight away
+    pub fn new(ctx: &mut Context, dropped: Option<PathBuf>) -> GameResult<Self> {
+        let title_text = UIText::new("Import Image".to_string(), Theme::fg_color(), 78.0, DrawablePos { x: 90.0, y: 90.0 });
+        let t_sz = title_text.text.measure(ctx)?;
+
+        let options = GameMenuItemList::new(
+            ctx,
+            vec![
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::InputItem {
+                        prompt: "Image File".to_string(),
+                        is_num: false,
+                        initial_value: "".to_string(),
+                    },
+                    next_page: None,
+                },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::TextItem { text: "Paste Path from Clipboard".to_string() },
+                    next_page: None,
+                },
+                NewGameMenuItemData { variant: NewGameMenuItemDataVariant::TextItem { text: "Import".to_string() }, next_page: None },
+            ],
+            90.0,
+            t_sz.y + 140.0,
+            900.0,
+            110.0,
+        )?;
+
+        let mut scene = Self { title_text, options, error_text: None, imported: None, back: false };
+        if let Some(path) = dropped {
+            scene.file_dropped_event(ctx, path);
+        }
+        Ok(scene)
+    }
+
+    fn show_error(&mut self, error: String) {
+        let y = self.options.items[IMPORT_ITEM].pos.y + 150.0;
+        self.error_text = Some(UIText::new(error, Theme::error_color(), 38.0, DrawablePos { x: 90.0, y }));
+    }
+
+    fn paste_path(&mut self) {
+        match clipboard::get_text() {
+            Ok(text) => {
+                // File managers put quotes around paths with spaces when they're copied
+                self.options.items[PATH_ITEM].set_input_value(text.trim().trim_matches('"').to_string());
+                self.error_text = None;
+            }
+            Err(_) => self.show_error("Couldn't read the clipboard".to_string()),
+        }
+    }
+
+    fn import(&mut self, path: &Path) {
+        if path.as_os_str().is_empty() {
+            return self.show_error("Enter the image's path first".to_string());
+        }
+        match ImageLoader::import(path) {
+            Ok(puzzle_id) => self.imported = Some(puzzle_id),
+            Err(e) => self.show_error(format!("Couldn't import the image: {}", error_message(&e))),
+        }
+    }
+
+    fn activate_selected(&mut self) {
+        match self.options.selected() {
+            PASTE_ITEM => self.paste_path(),
+            IMPORT_ITEM => {
+                let path = PathBuf::from(self.options.items[PATH_ITEM].get_input_value().unwrap().trim());
+                self.import(&path);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Drawable for ImportScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut ggez::graphics::Canvas) -> GameResult {
+        self.title_text.draw(ctx, canvas)?;
+        self.options.draw(ctx, canvas)?;
+        if let Some(error_text) = &mut self.error_text {
+            error_text.draw(ctx, canvas)?;
+        }
+        Ok(())
+    }
+}
+
+impl Scene for ImportScene {
+    fn handle_input_event(&mut self, ctx: &mut Context, key_input: InputAction) {
+        match key_input {
+            InputAction::Cancel => self.back = true,
+            InputAction::Select => self.activate_selected(),
+            _ => {}
+        }
+        self.options.handle_input_event(ctx, key_input);
+    }
+
+    fn handle_mouse_event(&mut self, ctx: &mut Context, mouse_input: MouseAction) {
+        self.options.handle_mouse_event(ctx, mouse_input);
+        if let MouseAction::Click(point) = mouse_input {
+            if self.options.items[self.options.selected()].contains(point) {
+                self.activate_selected();
+            }
+        }
+    }
+
+    fn text_input_event(&mut self, ctx: &mut Context, c: char) { self.options.text_input_event(ctx, c); }
+
+    fn file_dropped_event(&mut self, _ctx: &mut Context, path: PathBuf) {
+        self.options.items[PATH_ITEM].set_input_value(path.display().to_string());
+        self.import(&path);
+    }
+
+    fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
+        if let Some(puzzle_id) = self.imported.take() {
+            Some(try_scene(ctx, "Couldn't Show the Puzzle", move |ctx| PuzzleView::new(ctx, puzzle_id.clone()), main_menu))
+        } else if self.back {
+            Some(back_to_main_menu(ctx))
+        } else {
+            // None of the items have pages of their own
+            self.options.next_scene(ctx)
+        }
+    }
+}
@@ -1,3 +1,7 @@
+pub mod custom_board_scene;
+pub mod import_scene;
 pub mod puzzle_listing;
 pub mod puzzle_view;
+pub mod replay;
+pub mod replay_scene;
 pub mod tiles;
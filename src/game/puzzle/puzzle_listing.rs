@@ -1,174 +1,559 @@
-use ggez::{Context, GameResult};
+use std::{cmp::Reverse, collections::HashMap, path::PathBuf, sync::Mutex, time::Duration};
+
+use chrono::{DateTime, Local};
+use ggez::{
+    graphics::{DrawMode, DrawParam, Mesh, Rect},
+    Context, GameResult,
+};
+use lazy_static::lazy_static;
 
 use crate::game::{
     animation::DrawablePos,
     drawable::Drawable,
-    gmenu::{game_menu::GameMenu, main_menu::MainMenu, menu_item::GameMenuItem},
-    input::InputAction,
-    player::PLAYER,
-    puzzle::puzzle_view::PuzzleView,
-    resources::{image_loader::ImageLoader, theme::Theme},
+    gmenu::menu_item::GameMenuItem,
+    input::{mouse::MouseAction, InputAction},
+    player::{format_duration, PLAYER},
+    puzzle::{import_scene::ImportScene, puzzle_view::PuzzleView},
+    resources::{
+        image_loader::{ImageLoader, PuzzleId},
+        theme::Theme,
+    },
     scene::Scene,
-    ui::uitext::UIText,
+    ui::{
+        error_message,
+        error_scene::{back_to_main_menu, main_menu, try_scene},
+        error_toast,
+        uitext::UIText,
+    },
 };
 
+lazy_static! {
+    // Kept while the player goes in and out of puzzles
+    static ref QUERY: Mutex<ListingQuery> = Mutex::new(ListingQuery::default());
+}
+
+const MARGIN: f32 = 45.0;
+const ITEM_SIZE: f32 = 300.0;
+// Item plus the gap after it
+const ITEM_STRIDE: f32 = 320.0;
+const CONTROL_GAP: f32 = 40.0;
+
+// Indices into control_texts
+const SEARCH_CONTROL: usize = 0;
+const SHOW_CONTROL: usize = 1;
+const PACK_CONTROL: usize = 2;
+const SORT_CONTROL: usize = 3;
+const CONTROL_COUNT: usize = 4;
+
 #[derive(Debug)]
 enum PaginationDirection {
     Left,
     Right,
 }
 
-// Listing is 2x2
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum CompletionFilter {
+    #[default]
+    All,
+    Uncompleted,
+    Completed,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SortOrder {
+    // The order the packs list them in
+    #[default]
+    Listing,
+    Title,
+    BestTime,
+    LastPlayed,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ListingQuery {
+    // Matches titles and tags
+    search: String,
+    completion: CompletionFilter,
+    // Title of the pack, or every pack
+    pack: Option<String>,
+    sort: SortOrder,
+}
+
+impl ListingQuery {
+    // The puzzles to list, in order
+    fn puzzle_ids(&self) -> Vec<PuzzleId> {
+        // Best time and when the player last finished it
+        let played: HashMap<PuzzleId, (Duration, DateTime<Local>)> = {
+            let opt_player = PLAYER.lock().unwrap();
+            // Player guaranteed to be some at this point
+            let player = opt_player.as_ref().unwrap();
+            player
+                .completed_puzzles
+                .iter()
+                .filter_map(|(puzzle_id, stats)| {
+                    let last = stats.iter().map(|stat| stat.finish_time).max()?;
+                    let best = player.personal_bests(puzzle_id).map_or(Duration::MAX, |bests| bests.best_time);
+                    Some((puzzle_id.clone(), (best, last)))
+                })
+                .collect()
+        };
+
+        let search = self.search.trim().to_lowercase();
+        let mut puzzle_ids: Vec<PuzzleId> = ImageLoader::ids()
+            .into_iter()
+            .filter(|puzzle_id| match self.completion {
+                CompletionFilter::All => true,
+                CompletionFilter::Uncompleted => !played.contains_key(puzzle_id),
+                CompletionFilter::Completed => played.contains_key(puzzle_id),
+            })
+            .filter(|puzzle_id| {
+                let info = ImageLoader::info(puzzle_id).unwrap_or_default();
+                self.pack.is_none() || info.pack == self.pack
+            })
+            .filter(|puzzle_id| {
+                let info = ImageLoader::info(puzzle_id).unwrap_or_default();
+                search.is_empty()
+                    || ImageLoader::title(puzzle_id).to_lowercase().contains(&search)
+                    || info.tags.iter().any(|tag| tag.to_lowercase().contains(&search))
+            })
+            .collect();
+
+        // Sorts are stable, so ties and puzzles that haven't been played keep the listing order
+        match self.sort {
+            SortOrder::Listing => {}
+            SortOrder::Title => puzzle_ids.sort_by_cached_key(|puzzle_id| ImageLoader::title(puzzle_id).to_lowercase()),
+            SortOrder::BestTime => puzzle_ids.sort_by_key(|puzzle_id| played.get(puzzle_id).map_or(Duration::MAX, |(best, _)| *best)),
+            SortOrder::LastPlayed => puzzle_ids.sort_by_key(|puzzle_id| Reverse(played.get(puzzle_id).map(|(_, last)| *last))),
+        }
+        puzzle_ids
+    }
+
+    fn control_labels(&self) -> [String; CONTROL_COUNT] {
+        [
+            format!("Search: {}", if self.search.is_empty() { "type to search" } else { &self.search }),
+            format!(
+                "Show: {}",
+                match self.completion {
+                    CompletionFilter::All => "All",
+                    CompletionFilter::Uncompleted => "Not Completed",
+                    CompletionFilter::Completed => "Completed",
+                }
+            ),
+            format!("Pack: {}", self.pack.as_deref().unwrap_or("All")),
+            format!(
+                "Sort: {}",
+                match self.sort {
+                    SortOrder::Listing => "Pack Order",
+                    SortOrder::Title => "Title",
+                    SortOrder::BestTime => "Best Time",
+                    SortOrder::LastPlayed => "Last Played",
+                }
+            ),
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Focus {
+    // Index into control_texts
+    Control(usize),
+    // Index into menu_items
+    Puzzle(usize),
+}
+
+// As many columns and rows as fit in the window
 pub struct PuzzleListing {
     listing_start: usize,
-    currently_selected: (usize, usize),
-    menu_items: [[Option<GameMenuItem>; 2]; 2],
+    // Everything that matches the query, not just this page
+    puzzle_ids: Vec<PuzzleId>,
+    cols: usize,
+    rows: usize,
+    // To notice when the window is resized
+    window_size: (f32, f32),
+    focus: Focus,
+    // This page's puzzles, row by row
+    menu_items: Vec<GameMenuItem>,
     title_mesh: UIText,
+    control_texts: Vec<UIText>,
+    control_rects: Vec<Rect>,
+    controls_y: f32,
+    // Shown instead of the puzzles when there aren't any
+    empty_text: Option<UIText>,
     page_direction: Option<PaginationDirection>,
     back: bool,
     start_game: bool,
+    // Dragged onto the window, to be imported
+    dropped_file: Option<PathBuf>,
+    // Items still showing the placeholder
+    loading: Vec<(usize, PuzzleId)>,
+    // Best times on the puzzles that have been completed
+    badges: Vec<(Mesh, UIText)>,
 }
 
 impl PuzzleListing {
-    pub fn new(ctx: &mut Context, listing_start: usize) -> GameResult<Self> {
+    pub fn new(ctx: &mut Context, listing_start: usize) -> GameResult<Self> { Self::build(ctx, listing_start, None) }
+
+    // Opens the page the puzzle is on with it selected, e.g. when coming back from it
+    pub fn showing(ctx: &mut Context, puzzle_id: &PuzzleId) -> GameResult<Self> {
+        let mut listing = Self::new(ctx, 0)?;
+        let page_size = listing.page_size();
+        if let Some(idx) = listing.puzzle_ids.iter().position(|id| id == puzzle_id) {
+            let listing_start = page_size * (idx / page_size);
+            listing = Self::build(ctx, listing_start, Some(Focus::Puzzle(idx - listing_start)))?;
+        }
+        Ok(listing)
+    }
+
+    fn build(ctx: &mut Context, listing_start: usize, focus: Option<Focus>) -> GameResult<Self> {
+        let query = QUERY.lock().unwrap().clone();
+        let puzzle_ids = query.puzzle_ids();
+
+        let window_size = ctx.gfx.drawable_size();
+        // The title is one line whatever it says
+        let t_sz = UIText::new("Puzzles".to_string(), Theme::fg_color(), 78.0, DrawablePos { x: 0.0, y: 0.0 }).text.measure(ctx)?;
+        let controls_y = 55.0 + t_sz.y;
+        let grid_y = controls_y + 60.0;
+        let cols = (((window_size.0 - MARGIN) / ITEM_STRIDE) as usize).max(1);
+        let rows = (((window_size.1 - grid_y) / ITEM_STRIDE) as usize).max(1);
+        let page_size = cols * rows;
+
+        // The window may have shrunk or the query changed since the page was picked
+        let listing_start = if puzzle_ids.is_empty() {
+            0
+        } else {
+            page_size * (listing_start.min(puzzle_ids.len() - 1) / page_size)
+        };
+        let page = &puzzle_ids[listing_start..puzzle_ids.len().min(listing_start + page_size)];
+
         let title_mesh = UIText::new(
-            format!("Puzzles {} to {}", listing_start + 1, listing_start + 4),
+            if page.is_empty() {
+                "Puzzles".to_string()
+            } else {
+                format!("Puzzles {} to {} of {}", listing_start + 1, listing_start + page.len(), puzzle_ids.len())
+            },
             Theme::fg_color(),
             78.0,
-            DrawablePos { x: 45.0, y: 45.0 },
+            DrawablePos { x: MARGIN, y: MARGIN },
         );
 
-        let t_sz = title_mesh.text.measure(ctx)?;
-
-        let mut menu_items: [[Option<GameMenuItem>; 2]; 2] = [[None, None], [None, None]];
-
-        for i in 0..menu_items.len() {
-            for j in 0..menu_items[i].len() {
-                let puzzle_num = listing_start + (i * 2) + j;
-                if let Some(img) = ImageLoader::get_img(puzzle_num) {
-                    menu_items[i][j] = Some(GameMenuItem::new_image_item(
-                        ctx,
-                        img,
-                        &format!("Puzzle {}", puzzle_num + 1),
-                        // This should never happen, so we can panic if it does.
-                        Some(Box::new(|_| -> Box<dyn Scene> { panic!() })),
-                        45.0 + (j as f32 * 320.0),
-                        55.0 + t_sz.y + (i as f32 * 320.0),
-                        300.0,
-                        300.0,
-                    )?);
+        let mut menu_items = vec![];
+        let mut loading = vec![];
+        for (idx, puzzle_id) in page.iter().enumerate() {
+            let img = match ImageLoader::thumbnail(ctx, puzzle_id) {
+                Ok(Some(img)) => img,
+                Ok(None) => {
+                    loading.push((idx, puzzle_id.clone()));
+                    ImageLoader::placeholder()
+                }
+                Err(e) => {
+                    log::warn!("Couldn't load the thumbnail for {}: {}", puzzle_id, error_message(&e));
+                    ImageLoader::placeholder()
                 }
+            };
+            menu_items.push(GameMenuItem::new_image_item(
+                ctx,
+                img,
+                &ImageLoader::title(puzzle_id),
+                // The listing starts the game itself
+                None,
+                MARGIN + ((idx % cols) as f32 * ITEM_STRIDE),
+                grid_y + ((idx / cols) as f32 * ITEM_STRIDE),
+                ITEM_SIZE,
+                ITEM_SIZE,
+            )?);
+        }
+
+        let mut badges = vec![];
+        {
+            let opt_player = PLAYER.lock().unwrap();
+            let player = opt_player.as_ref().unwrap();
+            for (idx, puzzle_id) in page.iter().enumerate() {
+                let Some(bests) = player.personal_bests(puzzle_id) else { continue };
+                let pos = menu_items[idx].pos;
+                let badge_text = UIText::new(
+                    format!("Best {}", format_duration(bests.best_time)),
+                    Theme::bg_color(),
+                    24.0,
+                    DrawablePos { x: pos.x + 40.0, y: pos.y + 34.0 },
+                );
+                let size = badge_text.text.measure(ctx)?;
+                let rect = Rect { x: pos.x + 30.0, y: pos.y + 30.0, w: size.x + 20.0, h: size.y + 8.0 };
+                badges.push((Mesh::new_rounded_rectangle(ctx, DrawMode::fill(), rect, 6.0, Theme::sep_color())?, badge_text));
             }
         }
 
-        // TODO guarantee that at least one item is present
-        menu_items[0][0].as_mut().unwrap().select();
+        let empty_text = page.is_empty().then(|| {
+            let message = if ImageLoader::ids().is_empty() {
+                "There are no puzzles yet. Import an image from the main menu."
+            } else {
+                "No puzzles match. Try a different search or filter."
+            };
+            UIText::new(message.to_string(), Theme::fg_color(), 38.0, DrawablePos { x: MARGIN, y: grid_y })
+        });
 
-        Ok(Self { currently_selected: (0, 0), listing_start, title_mesh, menu_items, page_direction: None, back: false, start_game: false })
+        let focus = match focus {
+            _ if menu_items.is_empty() => Focus::Control(SEARCH_CONTROL),
+            Some(Focus::Puzzle(idx)) => Focus::Puzzle(idx.min(menu_items.len() - 1)),
+            Some(focus) => focus,
+            None => Focus::Puzzle(0),
+        };
+        if let Focus::Puzzle(idx) = focus {
+            menu_items[idx].select();
+        }
+
+        let mut listing = Self {
+            listing_start,
+            puzzle_ids,
+            cols,
+            rows,
+            window_size,
+            focus,
+            menu_items,
+            title_mesh,
+            control_texts: vec![],
+            control_rects: vec![],
+            controls_y,
+            empty_text,
+            page_direction: None,
+            back: false,
+            start_game: false,
+            dropped_file: None,
+            loading,
+            badges,
+        };
+        listing.update_controls(ctx, &query)?;
+        Ok(listing)
+    }
+
+    fn page_size(&self) -> usize { self.cols * self.rows }
+
+    // Lays the controls out in a row, the focused one highlighted
+    fn update_controls(&mut self, ctx: &mut Context, query: &ListingQuery) -> GameResult {
+        self.control_texts.clear();
+        self.control_rects.clear();
+        let mut x = MARGIN;
+        for (idx, label) in query.control_labels().into_iter().enumerate() {
+            let color = if self.focus == Focus::Control(idx) { Theme::border_color() } else { Theme::fg_color() };
+            let text = UIText::new(label, color, 34.0, DrawablePos { x, y: self.controls_y });
+            let size = text.text.measure(ctx)?;
+            self.control_rects.push(Rect { x, y: self.controls_y, w: size.x, h: size.y });
+            self.control_texts.push(text);
+            x += size.x + CONTROL_GAP;
+        }
+        Ok(())
+    }
+
+    fn set_focus(&mut self, ctx: &mut Context, focus: Focus) {
+        if let Focus::Puzzle(idx) = self.focus {
+            self.menu_items[idx].deselect();
+        }
+        if let Focus::Puzzle(idx) = focus {
+            self.menu_items[idx].select();
+        }
+        self.focus = focus;
+        let query = QUERY.lock().unwrap().clone();
+        if let Err(e) = self.update_controls(ctx, &query) {
+            error_toast::report_error("Couldn't lay out the listing", &e);
+        }
+    }
+
+    // Starts again from the first page after the query changes
+    fn refresh(&mut self, ctx: &mut Context) {
+        match Self::build(ctx, 0, Some(self.focus)) {
+            Ok(listing) => *self = listing,
+            // The listing as it was is still usable
+            Err(e) => error_toast::report_error("Couldn't update the puzzles", &e),
+        }
+    }
+
+    fn activate_control(&mut self, ctx: &mut Context, control: usize) {
+        {
+            let mut query = QUERY.lock().unwrap();
+            match control {
+                // Typed into rather than picked from
+                SEARCH_CONTROL => return,
+                SHOW_CONTROL =>
+                    query.completion = match query.completion {
+                        CompletionFilter::All => CompletionFilter::Uncompleted,
+                        CompletionFilter::Uncompleted => CompletionFilter::Completed,
+                        CompletionFilter::Completed => CompletionFilter::All,
+                    },
+                PACK_CONTROL => {
+                    // All, then each pack in turn
+                    let packs = ImageLoader::packs();
+                    let next = match &query.pack {
+                        None => 0,
+                        Some(pack) => packs.iter().position(|p| p == pack).map_or(packs.len(), |idx| idx + 1),
+                    };
+                    query.pack = packs.get(next).cloned();
+                }
+                SORT_CONTROL =>
+                    query.sort = match query.sort {
+                        SortOrder::Listing => SortOrder::Title,
+                        SortOrder::Title => SortOrder::BestTime,
+                        SortOrder::BestTime => SortOrder::LastPlayed,
+                        SortOrder::LastPlayed => SortOrder::Listing,
+                    },
+                _ => {}
+            }
+        }
+        self.refresh(ctx);
     }
 }
 
 impl Drawable for PuzzleListing {
     fn draw(&mut self, ctx: &mut ggez::Context, canvas: &mut ggez::graphics::Canvas) -> ggez::GameResult {
         self.title_mesh.draw(ctx, canvas)?;
-
-        for listing_row in &mut self.menu_items {
-            for listing in listing_row {
-                if let Some(listing) = listing {
-                    listing.draw(ctx, canvas)?;
-                }
-            }
+        for control_text in self.control_texts.iter_mut() {
+            control_text.draw(ctx, canvas)?;
+        }
+        for listing in self.menu_items.iter_mut() {
+            listing.draw(ctx, canvas)?;
+        }
+        for (badge_rect, badge_text) in self.badges.iter_mut() {
+            canvas.draw(badge_rect, DrawParam::default());
+            badge_text.draw(ctx, canvas)?;
+        }
+        if let Some(empty_text) = &mut self.empty_text {
+            empty_text.draw(ctx, canvas)?;
         }
         Ok(())
     }
 }
 
 impl Scene for PuzzleListing {
-    fn handle_input_event(&mut self, _ctx: &mut ggez::Context, key_input: InputAction) {
-        let old_selected = self.currently_selected;
+    fn handle_input_event(&mut self, ctx: &mut ggez::Context, key_input: InputAction) {
+        let (cols, len) = (self.cols, self.menu_items.len());
+        match (self.focus, key_input) {
+            (_, InputAction::Cancel) => self.back = true,
 
-        let (o_i, o_j) = old_selected;
-
-        match key_input {
-            InputAction::Up =>
-                if self.currently_selected.0 > 0 {
-                    self.currently_selected.0 -= 1;
-                },
+            (Focus::Control(control), InputAction::Left) if control > 0 => self.set_focus(ctx, Focus::Control(control - 1)),
+            (Focus::Control(control), InputAction::Right) if control + 1 < CONTROL_COUNT =>
+                self.set_focus(ctx, Focus::Control(control + 1)),
+            (Focus::Control(_), InputAction::Down) if len > 0 => self.set_focus(ctx, Focus::Puzzle(0)),
+            // Done searching
+            (Focus::Control(SEARCH_CONTROL), InputAction::Select) if len > 0 => self.set_focus(ctx, Focus::Puzzle(0)),
+            (Focus::Control(control), InputAction::Select) => self.activate_control(ctx, control),
 
-            InputAction::Down =>
-                if self.currently_selected.0 < 1 && self.menu_items[o_i + 1][o_j].is_some() {
-                    self.currently_selected.0 += 1;
-                },
-            InputAction::Left => {
-                if self.currently_selected.1 > 0 {
-                    self.currently_selected.1 -= 1;
+            // The controls are above the first row
+            (Focus::Puzzle(idx), InputAction::Up) =>
+                self.set_focus(ctx, if idx >= cols { Focus::Puzzle(idx - cols) } else { Focus::Control(SEARCH_CONTROL) }),
+            (Focus::Puzzle(idx), InputAction::Down) if idx + cols < len => self.set_focus(ctx, Focus::Puzzle(idx + cols)),
+            (Focus::Puzzle(idx), InputAction::Left) =>
+                if idx % cols > 0 {
+                    self.set_focus(ctx, Focus::Puzzle(idx - 1));
                 } else {
-                    // Page left
                     self.page_direction = Some(PaginationDirection::Left);
-                }
-            }
-            InputAction::Right => {
-                if self.currently_selected.1 < 1 && self.menu_items[o_i][o_j + 1].is_some() {
-                    self.currently_selected.1 += 1;
+                },
+            (Focus::Puzzle(idx), InputAction::Right) =>
+                if idx % cols + 1 < cols && idx + 1 < len {
+                    self.set_focus(ctx, Focus::Puzzle(idx + 1));
                 } else {
-                    // Page right
                     self.page_direction = Some(PaginationDirection::Right);
+                },
+            (Focus::Puzzle(_), InputAction::Select) => self.start_game = true,
+            _ => {}
+        }
+    }
+
+    fn handle_mouse_event(&mut self, ctx: &mut ggez::Context, mouse_input: MouseAction) {
+        match mouse_input {
+            MouseAction::Hover(point) | MouseAction::Click(point) => {
+                let hovered = if let Some(idx) = self.menu_items.iter().position(|item| item.contains(point)) {
+                    Focus::Puzzle(idx)
+                } else if let Some(control) = self.control_rects.iter().position(|rect| rect.contains(point)) {
+                    Focus::Control(control)
+                } else {
+                    return;
+                };
+                if hovered != self.focus {
+                    self.set_focus(ctx, hovered);
+                }
+                if let MouseAction::Click(_) = mouse_input {
+                    match hovered {
+                        Focus::Puzzle(_) => self.start_game = true,
+                        Focus::Control(control) => self.activate_control(ctx, control),
+                    }
                 }
             }
-            InputAction::Cancel => {
-                self.back = true;
-            }
-            InputAction::Select => {
-                self.start_game = true;
+            // Swipe across the listing to turn the page
+            MouseAction::Drag { from, to } if (to.x - from.x).abs() > (to.y - from.y).abs() => {
+                self.page_direction = Some(if to.x < from.x { PaginationDirection::Right } else { PaginationDirection::Left });
             }
-            _ => {}
-        }
-        if old_selected != self.currently_selected {
-            self.menu_items[old_selected.0][old_selected.1].as_mut().unwrap().deselect();
-            self.menu_items[self.currently_selected.0][self.currently_selected.1].as_mut().unwrap().select();
+            MouseAction::Drag { .. } => {}
         }
     }
 
     fn next_scene(&mut self, ctx: &mut ggez::Context) -> Option<Box<dyn Scene>> {
-        if self.back {
-            return Some(Box::new(GameMenu::new::<MainMenu>(ctx).expect("Failed to launch game menu")));
+        if let Some(path) = self.dropped_file.take() {
+            return Some(try_scene(ctx, "Couldn't Open the Import", move |ctx| ImportScene::new(ctx, Some(path.clone())), main_menu));
+        } else if self.back {
+            return Some(back_to_main_menu(ctx));
         } else if self.start_game {
-            let opt_player = PLAYER.lock().unwrap();
-            // Player guaranteed to be some at this point
-            let _player = opt_player.as_ref().unwrap();
-
-            let game_image_num = self.listing_start + (self.currently_selected.0 * 2) + self.currently_selected.1;
-
-            println!("starting tile state {}", game_image_num);
-            return Some(
-                // TODO move this the puzzle view
-                Box::new(PuzzleView::new(ctx, game_image_num).expect("Failed to create tile state")),
-            );
+            self.start_game = false;
+            let Focus::Puzzle(idx) = self.focus else { return None };
+            let puzzle_id = self.puzzle_ids.get(self.listing_start + idx)?.clone();
+            let listing_start = self.listing_start;
+            return Some(try_scene(
+                ctx,
+                "Couldn't Show the Puzzle",
+                move |ctx| PuzzleView::new(ctx, puzzle_id.clone()),
+                move |ctx| Ok(Box::new(Self::new(ctx, listing_start)?)),
+            ));
         }
 
-        let check_listing = match self.page_direction {
+        let page_size = self.page_size();
+        let check_listing = match self.page_direction.take() {
             None => return None,
-            Some(PaginationDirection::Left) =>
-                if self.listing_start >= 4 {
-                    self.listing_start - 4
-                } else {
-                    return None;
-                },
-
-            Some(PaginationDirection::Right) => self.listing_start + 4,
+            Some(PaginationDirection::Left) if self.listing_start >= page_size => self.listing_start - page_size,
+            Some(PaginationDirection::Left) => return None,
+            Some(PaginationDirection::Right) if self.listing_start + page_size < self.puzzle_ids.len() => self.listing_start + page_size,
+            Some(PaginationDirection::Right) => return None,
         };
-
-        if ImageLoader::has_img(check_listing) {
-            Some(Box::new(Self::new(ctx, check_listing).expect("Failed to make prev page of puzzles")))
-        } else {
-            self.page_direction = None;
-            None
-        }
+        Some(try_scene(ctx, "Couldn't Show the Puzzles", move |ctx| Self::new(ctx, check_listing), main_menu))
     }
 
     fn draw_transition(&mut self, ctx: &mut ggez::Context, canvas: &mut ggez::graphics::Canvas) -> ggez::GameResult {
         self.draw(ctx, canvas)
     }
 
-    fn text_input_event(&mut self, _ctx: &mut ggez::Context, _c: char) {}
+    // Typing anywhere in the listing searches
+    fn text_input_event(&mut self, ctx: &mut ggez::Context, c: char) {
+        {
+            let mut query = QUERY.lock().unwrap();
+            // Backspace
+            if c == '\x08' {
+                query.search.pop();
+            } else if !c.is_control() {
+                query.search.push(c);
+            } else {
+                return;
+            }
+        }
+        self.focus = Focus::Control(SEARCH_CONTROL);
+        self.refresh(ctx);
+    }
+
+    fn file_dropped_event(&mut self, _ctx: &mut ggez::Context, path: PathBuf) { self.dropped_file = Some(path); }
+
+    fn update(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult {
+        if ctx.gfx.drawable_size() != self.window_size {
+            *self = Self::build(ctx, self.listing_start, Some(self.focus))?;
+        }
+
+        let menu_items = &mut self.menu_items;
+        // Ones that failed keep the placeholder
+        self.loading.retain(|(idx, puzzle_id)| match ImageLoader::thumbnail(ctx, puzzle_id) {
+            Ok(Some(img)) => {
+                menu_items[*idx].set_image(img);
+                false
+            }
+            Ok(None) => true,
+            Err(e) => {
+                log::warn!("Couldn't load the thumbnail for {}: {}", puzzle_id, error_message(&e));
+                false
+            }
+        });
+        Ok(())
+    }
 }
@@ -12,7 +12,7 @@
 // -> Display string + copy to clipboard
 // -> Start game
 
-use std::sync::Arc;
+use std::{cell::Cell, rc::Rc, sync::Arc};
 
 use ggez::{
     graphics::{DrawParam, Image},
@@ -23,69 +23,149 @@ use crate::game::{
     animation::DrawablePos,
     drawable::Drawable,
     gmenu::menu_item_list::{GameMenuItemList, NewGameMenuItemData, NewGameMenuItemDataVariant},
-    input::InputAction,
+    input::{mouse::MouseAction, InputAction},
     multiplayer::join_scene::JoinMultiplayerScene,
-    player::PLAYER,
-    puzzle::{puzzle_listing::PuzzleListing, tiles::TileState},
-    resources::{image_loader::ImageLoader, theme::Theme},
+    player::{format_duration, PLAYER},
+    puzzle::{
+        puzzle_listing::PuzzleListing,
+        tiles::{tile_random::TileRandom, Scramble, TileState},
+    },
+    resources::{
+        image_loader::{ImageLoader, PuzzleId},
+        theme::Theme,
+    },
     scene::Scene,
-    ui::uitext::UIText,
+    ui::{
+        error_message,
+        error_scene::{main_menu, try_scene},
+        uitext::UIText,
+    },
 };
 
 pub struct PuzzleView {
     title_text: UIText,
 
-    // Image
+    // Image, the placeholder until the thumbnail has loaded
     puzzle_image: Arc<Image>,
+    image_loading: bool,
+    // Author, license etc. next to the image
+    credits_text: Vec<UIText>,
 
     puzzle_action_mappings: GameMenuItemList,
-    puzzle_num: usize,
+    puzzle_id: PuzzleId,
+    // Shared with the menu callbacks so games start with whatever was typed in
+    scramble: Rc<Cell<Scramble>>,
 
     back: bool,
 }
 
-fn create_singleplayer_game(context: &mut Context, puzzle_num: usize) -> Box<dyn Scene> {
-    let opt_player = PLAYER.lock().unwrap();
-    let player = opt_player.as_ref().unwrap();
-    let pos = TileState::center_xy(context);
-    Box::new(
-        TileState::new_singleplayer(context, puzzle_num, player.player_settings.num_rows_cols, pos)
-            .expect("Failed to create singleplayer game"),
-    )
+// Index of the scramble input in puzzle_action_mappings
+const SCRAMBLE_ITEM: usize = 3;
+
+// Where the games started from here go back to when they can't be made
+pub fn back_to_puzzle(puzzle_id: &PuzzleId) -> impl Fn(&mut Context) -> GameResult<Box<dyn Scene>> {
+    let puzzle_id = puzzle_id.clone();
+    move |ctx| Ok(Box::new(PuzzleView::new(ctx, puzzle_id.clone())?))
+}
+
+fn create_singleplayer_game(context: &mut Context, puzzle_id: &PuzzleId, scramble: Scramble) -> Box<dyn Scene> {
+    let id = puzzle_id.clone();
+    let make = move |ctx: &mut Context| {
+        let num_rows_cols = PLAYER.lock().unwrap().as_ref().unwrap().player_settings.num_rows_cols();
+        let pos = TileState::center_xy(ctx);
+        TileState::new_singleplayer(ctx, &id, num_rows_cols, scramble, pos)
+    };
+    try_scene(context, "Couldn't Start the Game", make, back_to_puzzle(puzzle_id))
 }
 
-fn create_multiplayer_game(context: &mut Context, puzzle_num: usize) -> Box<dyn Scene> {
-    Box::new(JoinMultiplayerScene::new(context, puzzle_num, true).expect("Failed to create join multiplayer scene"))
+fn create_auto_solve_game(context: &mut Context, puzzle_id: &PuzzleId, scramble: Scramble) -> Box<dyn Scene> {
+    let id = puzzle_id.clone();
+    let make = move |ctx: &mut Context| {
+        // auto_solve needs the player lock, so it isn't held here
+        let num_rows_cols = PLAYER.lock().unwrap().as_ref().unwrap().player_settings.num_rows_cols();
+        let pos = TileState::center_xy(ctx);
+        let mut tile_state = TileState::new_singleplayer(ctx, &id, num_rows_cols, scramble, pos)?;
+        tile_state.auto_solve();
+        Ok(tile_state)
+    };
+    try_scene(context, "Couldn't Start the Game", make, back_to_puzzle(puzzle_id))
+}
+
+fn create_multiplayer_game(context: &mut Context, puzzle_id: &PuzzleId, scramble: Scramble) -> Box<dyn Scene> {
+    let id = puzzle_id.clone();
+    try_scene(
+        context,
+        "Couldn't Create a Multiplayer Game",
+        move |ctx| JoinMultiplayerScene::new(ctx, id.clone(), scramble, true),
+        back_to_puzzle(puzzle_id),
+    )
 }
 
 impl PuzzleView {
-    pub fn new(ctx: &mut Context, puzzle_num: usize) -> GameResult<Self> {
+    pub fn new(ctx: &mut Context, puzzle_id: PuzzleId) -> GameResult<Self> {
+        let scramble = Rc::new(Cell::new(Scramble::default()));
+        let (sp_scramble, mp_scramble, as_scramble) = (scramble.clone(), scramble.clone(), scramble.clone());
+        let (sp_id, mp_id, as_id) = (puzzle_id.clone(), puzzle_id.clone(), puzzle_id.clone());
         let puzzle_action_mappings = GameMenuItemList::new(
             ctx,
             vec![
                 NewGameMenuItemData {
                     variant: NewGameMenuItemDataVariant::TextItem { text: "Play as Singleplayer".to_string() },
-                    next_page: Some(Box::new(move |c| create_singleplayer_game(c, puzzle_num))),
+                    next_page: Some(Box::new(move |c| create_singleplayer_game(c, &sp_id, sp_scramble.get()))),
                 },
                 NewGameMenuItemData {
                     variant: NewGameMenuItemDataVariant::TextItem { text: "Create Multiplayer Game".to_string() },
-                    next_page: Some(Box::new(move |c| create_multiplayer_game(c, puzzle_num))),
+                    next_page: Some(Box::new(move |c| create_multiplayer_game(c, &mp_id, mp_scramble.get()))),
+                },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::TextItem { text: "Watch Auto-solve".to_string() },
+                    next_page: Some(Box::new(move |c| create_auto_solve_game(c, &as_id, as_scramble.get()))),
+                },
+                NewGameMenuItemData {
+                    variant: NewGameMenuItemDataVariant::InputItem {
+                        is_num: true,
+                        prompt: "Minimum Moves (blank for random)".to_string(),
+                        initial_value: "".to_string(),
+                    },
+                    next_page: None,
                 },
             ],
             90.0,
             // How do we know this position for sure?
             520.0,
-            500.0,
-            80.0,
+            700.0,
+            100.0,
         )?;
-        Ok(Self {
-            title_text: UIText::new(format!("Puzzle {}", puzzle_num + 1), Theme::fg_color(), 78.0, DrawablePos { x: 90.0, y: 90.0 }),
-            back: false,
-            puzzle_num,
-            puzzle_action_mappings,
-            // We can panic here since the image should always be valid
-            puzzle_image: ImageLoader::get_img(puzzle_num).expect("Incorrect image provided to ImageLoader"),
-        })
+        // A thumbnail that failed isn't waited for
+        let (puzzle_image, image_loading) = match ImageLoader::thumbnail(ctx, &puzzle_id) {
+            Ok(Some(thumbnail)) => (thumbnail, false),
+            Ok(None) => (ImageLoader::placeholder(), true),
+            Err(e) => {
+                log::warn!("Couldn't load the thumbnail for {}: {}", puzzle_id, error_message(&e));
+                (ImageLoader::placeholder(), false)
+            }
+        };
+        // Most likely to be played next
+        ImageLoader::prefetch(ctx, &puzzle_id);
+
+        let title_text = UIText::new(ImageLoader::title(&puzzle_id), Theme::fg_color(), 78.0, DrawablePos { x: 90.0, y: 90.0 });
+        let credits_y = 90.0 + title_text.text.measure(ctx)?.y + 20.0;
+        // The player's personal best goes above the pack's credits
+        let bests = PLAYER
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|player| Some((player.player_settings.num_rows_cols(), player.personal_bests(&puzzle_id)?)));
+        let credits_text = bests
+            .map(|((rows, cols), bests)| {
+                format!("Best at {}x{}: {} and {} moves", rows, cols, format_duration(bests.best_time), bests.best_moves)
+            })
+            .into_iter()
+            .chain(ImageLoader::info(&puzzle_id).map_or(vec![], |info| info.credits()))
+            .enumerate()
+            .map(|(idx, line)| UIText::new(line, Theme::fg_color(), 34.0, DrawablePos { x: 420.0, y: credits_y + idx as f32 * 44.0 }))
+            .collect();
+        Ok(Self { title_text, credits_text, back: false, puzzle_image, image_loading, puzzle_id, puzzle_action_mappings, scramble })
     }
 }
 
@@ -96,6 +176,9 @@ impl Drawable for PuzzleView {
         canvas.draw(&*self.puzzle_image, DrawParam::from([90.0, 90.0 + text_dim.y + 20.0]).scale([scale_factor; 2]));
         self.puzzle_action_mappings.draw(ctx, canvas)?;
         self.title_text.draw(ctx, canvas)?;
+        for credit_text in self.credits_text.iter_mut() {
+            credit_text.draw(ctx, canvas)?;
+        }
         Ok(())
     }
 }
@@ -108,10 +191,52 @@ impl Scene for PuzzleView {
         self.puzzle_action_mappings.handle_input_event(ctx, key_input);
     }
 
+    fn handle_mouse_event(&mut self, ctx: &mut Context, mouse_input: MouseAction) {
+        self.puzzle_action_mappings.handle_mouse_event(ctx, mouse_input);
+    }
+
+    fn text_input_event(&mut self, ctx: &mut Context, c: char) {
+        self.puzzle_action_mappings.text_input_event(ctx, c);
+
+        let min_moves = self.puzzle_action_mappings.items[SCRAMBLE_ITEM].get_input_value().unwrap_or_default();
+        self.scramble.set(match min_moves.parse::<u32>() {
+            Ok(min_moves) if min_moves > 0 => {
+                // Anything more can't be reached at the player's board size
+                let (rows, cols) = PLAYER.lock().unwrap().as_ref().map_or((4, 4), |player| player.player_settings.num_rows_cols());
+                let max = TileRandom::max_min_moves(rows, cols);
+                if min_moves > max {
+                    self.puzzle_action_mappings.items[SCRAMBLE_ITEM].set_input_value(max.to_string());
+                }
+                Scramble::MinMoves(min_moves.min(max))
+            }
+            _ => Scramble::Random,
+        });
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        if self.image_loading {
+            match ImageLoader::thumbnail(ctx, &self.puzzle_id) {
+                Ok(Some(thumbnail)) => {
+                    self.puzzle_image = thumbnail;
+                    self.image_loading = false;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::warn!("Couldn't load the thumbnail for {}: {}", self.puzzle_id, error_message(&e));
+                    self.image_loading = false;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
         match self.puzzle_action_mappings.next_scene(ctx) {
             Some(next_scene) => Some(next_scene),
-            None if self.back => Some(Box::new(PuzzleListing::new(ctx, self.puzzle_num / 4).expect("Failed to return to puzzle listing"))),
+            None if self.back => {
+                let puzzle_id = self.puzzle_id.clone();
+                Some(try_scene(ctx, "Couldn't Return to the Puzzles", move |ctx| PuzzleListing::showing(ctx, &puzzle_id), main_menu))
+            }
             None => None,
         }
     }
@@ -0,0 +1,58 @@
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+    time::Duration,
+};
+
+use ggez::{GameError, GameResult};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::game::{player::save_dir, resources::image_loader::PuzzleId};
+
+use super::tiles::{Board, Move};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct ReplayMove {
+    // Since the game was created
+    pub time: Duration,
+    pub mv: Move,
+}
+
+// Everything needed to play a game back move by move. Replays are kept in
+// their own files next to player.dat so loading the player stays quick.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Replay {
+    pub id: Uuid,
+    pub puzzle_id: PuzzleId,
+    // The scrambled board the game started from
+    pub board: Board,
+    pub moves: Vec<ReplayMove>,
+}
+
+impl Replay {
+    pub fn new(puzzle_id: PuzzleId, board: Board) -> Self { Self { id: Uuid::new_v4(), puzzle_id, board, moves: vec![] } }
+
+    pub fn record(&mut self, time: Duration, mv: Move) { self.moves.push(ReplayMove { time, mv }); }
+
+    fn path(id: Uuid) -> PathBuf { save_dir().join("replays").join(format!("{}.dat", id)) }
+
+    pub fn exists(id: Uuid) -> bool { Self::path(id).is_file() }
+
+    pub fn load(id: Uuid) -> GameResult<Self> {
+        let replay_file = File::open(Self::path(id))?;
+        bincode::deserialize_from(replay_file).map_err(|_| GameError::FilesystemError(format!("Failed to read replay {}", id)))
+    }
+
+    pub fn delete(id: Uuid) {
+        if let Err(e) = fs::remove_file(Self::path(id)) {
+            log::warn!("Couldn't delete replay {}: {}", id, e);
+        }
+    }
+
+    pub fn save(&self) -> GameResult {
+        fs::create_dir_all(save_dir().join("replays"))?;
+        let replay_file = File::create(Self::path(self.id))?;
+        bincode::serialize_into(replay_file, self).map_err(|_| GameError::FilesystemError(format!("Failed to save replay {}", self.id)))
+    }
+}
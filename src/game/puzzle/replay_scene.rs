@@ -0,0 +1,169 @@
+// Plays a recorded game back on the normal tile board
+//
+// Enter -> pause/play
+// Left/Right -> step one move back/forward
+// Up/Down -> faster/slower
+
+use std::time::Duration;
+
+use ggez::{graphics::Canvas, Context, GameResult};
+
+use crate::game::{
+    animation::DrawablePos,
+    drawable::Drawable,
+    gmenu::{game_menu::GameMenu, history_menu::HistoryMenu},
+    input::InputAction,
+    resources::theme::Theme,
+    scene::Scene,
+    ui::{
+        error_scene::{main_menu, try_scene},
+        uitext::UIText,
+    },
+};
+
+use super::{
+    replay::Replay,
+    tiles::{tile_state::TILE_SLIDE_DURATION, TileState},
+};
+
+const MIN_SPEED: f32 = 0.25;
+const MAX_SPEED: f32 = 16.0;
+// How long to wait before the first move
+const LEAD_IN: Duration = Duration::from_millis(500);
+
+pub struct ReplayScene {
+    tile_state: TileState,
+    replay: Replay,
+
+    // Index of the next move to play
+    next_move: usize,
+    // Where playback is in the recording
+    clock: Duration,
+    paused: bool,
+    speed: f32,
+
+    status_text: UIText,
+    controls_text: UIText,
+
+    back: bool,
+}
+
+impl ReplayScene {
+    pub fn new(ctx: &mut Context, replay: Replay) -> GameResult<Self> {
+        let pos = TileState::center_xy(ctx);
+        let tile_state = TileState::new_replay(ctx, &replay.puzzle_id, &replay.board, pos)?;
+        // Skip however long it took the player to make their first move
+        let clock = replay.moves.first().map_or(Duration::ZERO, |first| first.time.saturating_sub(LEAD_IN));
+
+        let mut replay_scene = Self {
+            tile_state,
+            replay,
+            next_move: 0,
+            clock,
+            paused: false,
+            speed: 1.0,
+            status_text: UIText::new("".to_string(), Theme::fg_color(), 48.0, DrawablePos { x: 90.0, y: 45.0 }),
+            controls_text: UIText::new(
+                "Enter: Pause   Left/Right: Step   Up/Down: Speed".to_string(),
+                Theme::fg_color(),
+                28.0,
+                DrawablePos { x: 90.0, y: ctx.gfx.drawable_size().1 - 75.0 },
+            ),
+            back: false,
+        };
+        replay_scene.update_status();
+        Ok(replay_scene)
+    }
+
+    fn update_status(&mut self) {
+        let state = if self.next_move == self.replay.moves.len() {
+            "Finished"
+        } else if self.paused {
+            "Paused"
+        } else {
+            "Playing"
+        };
+        self.status_text = UIText::new(
+            format!("Replay - Move {} of {} - {}x - {}", self.next_move, self.replay.moves.len(), self.speed, state),
+            Theme::fg_color(),
+            48.0,
+            self.status_text.pos,
+        );
+    }
+
+    fn step_forward(&mut self) {
+        let Some(replay_move) = self.replay.moves.get(self.next_move) else { return };
+        let Some(tile) = self.tile_state.board.move_source(replay_move.mv) else { return };
+        self.tile_state.swap_ref_tiles(self.tile_state.board.blank_cell(), tile, TILE_SLIDE_DURATION / self.speed.max(1.0));
+        self.clock = self.clock.max(replay_move.time);
+        self.next_move += 1;
+    }
+
+    fn step_back(&mut self) {
+        if self.next_move > 0 && self.tile_state.undo_move() > 0 {
+            self.next_move -= 1;
+            // Pick up right after the move before this one
+            if self.next_move > 0 {
+                self.clock = self.replay.moves[self.next_move - 1].time;
+            }
+        }
+    }
+}
+
+impl Drawable for ReplayScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        self.tile_state.draw(ctx, canvas)?;
+        self.status_text.draw(ctx, canvas)?;
+        self.controls_text.draw(ctx, canvas)?;
+        Ok(())
+    }
+}
+
+impl Scene for ReplayScene {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        if self.paused || !self.tile_state.started() || self.next_move == self.replay.moves.len() {
+            return Ok(());
+        }
+        self.clock += ctx.time.delta().mul_f32(self.speed);
+        while self.replay.moves.get(self.next_move).is_some_and(|replay_move| replay_move.time <= self.clock) {
+            self.step_forward();
+        }
+        self.update_status();
+        Ok(())
+    }
+
+    fn handle_input_event(&mut self, _ctx: &mut Context, key_input: InputAction) {
+        if !self.tile_state.started() {
+            if let InputAction::Cancel = key_input {
+                self.back = true;
+            }
+            return;
+        }
+        match key_input {
+            InputAction::Select => self.paused = !self.paused,
+            InputAction::Right => {
+                self.paused = true;
+                self.step_forward();
+            }
+            InputAction::Left => {
+                self.paused = true;
+                self.step_back();
+            }
+            InputAction::Up => self.speed = (self.speed * 2.0).min(MAX_SPEED),
+            InputAction::Down => self.speed = (self.speed / 2.0).max(MIN_SPEED),
+            InputAction::Cancel => self.back = true,
+            _ => {}
+        }
+        self.update_status();
+    }
+
+    fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
+        if self.back {
+            Some(try_scene(ctx, "Couldn't Return to the History", GameMenu::new::<HistoryMenu>, main_menu))
+        } else {
+            None
+        }
+    }
+
+    fn draw_transition(&mut self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult { self.tile_state.draw_transition(ctx, canvas) }
+}
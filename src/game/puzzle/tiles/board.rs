@@ -0,0 +1,424 @@
+// Headless model of a sliding puzzle board. This has no dependency on ggez,
+// so it can be used without a window (scripts, CI, solvers, ...).
+//
+// Tiles are identified by the row-major index of the cell they occupy when
+// the puzzle is solved. One tile is removed from the board to make the blank,
+// and the board keeps track of which tile that was.
+
+use serde::{Deserialize, Serialize};
+
+// The direction a tile slides into the blank cell.
+// i.e. Up means the tile below the blank slides up into it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Move {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Move {
+    pub const ALL: [Move; 4] = [Move::Up, Move::Down, Move::Left, Move::Right];
+
+    pub fn inverse(self) -> Self {
+        match self {
+            Move::Up => Move::Down,
+            Move::Down => Move::Up,
+            Move::Left => Move::Right,
+            Move::Right => Move::Left,
+        }
+    }
+
+    // Offset of the sliding tile relative to the blank cell
+    pub fn tile_offset(self) -> (isize, isize) {
+        match self {
+            Move::Up => (1, 0),
+            Move::Down => (-1, 0),
+            Move::Left => (0, 1),
+            Move::Right => (0, -1),
+        }
+    }
+
+    pub fn from_tile_offset(offset: (isize, isize)) -> Option<Self> { Move::ALL.into_iter().find(|&mv| mv.tile_offset() == offset) }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Board {
+    rows: usize,
+    cols: usize,
+    // cells[i * cols + j] is the tile currently at (i, j)
+    cells: Vec<usize>,
+    blank_tile: usize,
+    // For efficiency purposes
+    blank_cell: (usize, usize),
+    // The moves each input played, a line slide is several. Undo and redo
+    // work on a whole input at a time.
+    history: Vec<Vec<Move>>,
+    // Inputs that were undone, most recent last
+    redo_stack: Vec<Vec<Move>>,
+}
+
+// Two boards are the same if their tiles are laid out the same way,
+// no matter how they got there.
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows && self.cols == other.cols && self.blank_tile == other.blank_tile && self.cells == other.cells
+    }
+}
+
+impl Eq for Board {}
+
+impl Board {
+    // Creates a solved board with the tile at blank_cell removed.
+    pub fn new(rows: usize, cols: usize, blank_cell: (usize, usize)) -> Self {
+        assert!(blank_cell.0 < rows && blank_cell.1 < cols, "Blank cell is outside of the board");
+        Self {
+            rows,
+            cols,
+            cells: (0..rows * cols).collect(),
+            blank_tile: blank_cell.0 * cols + blank_cell.1,
+            blank_cell,
+            history: vec![],
+            redo_stack: vec![],
+        }
+    }
+
+    // Creates a board from a row-major list of tiles. Returns None if
+    // the cells are not a permutation of the tiles of a rows x cols board.
+    pub fn from_cells(rows: usize, cols: usize, cells: Vec<usize>, blank_tile: usize) -> Option<Self> {
+        if rows == 0 || cols == 0 || cells.len() != rows * cols || blank_tile >= cells.len() {
+            return None;
+        }
+        let mut seen = vec![false; cells.len()];
+        for &tile in &cells {
+            if tile >= cells.len() || seen[tile] {
+                return None;
+            }
+            seen[tile] = true;
+        }
+        let blank_idx = cells.iter().position(|&t| t == blank_tile)?;
+        Some(Self { rows, cols, cells, blank_tile, blank_cell: (blank_idx / cols, blank_idx % cols), history: vec![], redo_stack: vec![] })
+    }
+
+    pub fn rows(&self) -> usize { self.rows }
+    pub fn cols(&self) -> usize { self.cols }
+    pub fn cells(&self) -> &[usize] { &self.cells }
+    pub fn blank_cell(&self) -> (usize, usize) { self.blank_cell }
+    pub fn blank_tile(&self) -> usize { self.blank_tile }
+    // Every move played, oldest first
+    pub fn history(&self) -> Vec<Move> { self.history.concat() }
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.redo_stack.clear();
+    }
+
+    // The tile at (i, j), or None if that is the blank cell
+    pub fn tile_at(&self, (i, j): (usize, usize)) -> Option<usize> {
+        let tile = self.cells[i * self.cols + j];
+        if tile == self.blank_tile {
+            None
+        } else {
+            Some(tile)
+        }
+    }
+
+    // Where a tile is when the board is solved
+    pub fn home_of(&self, tile: usize) -> (usize, usize) { (tile / self.cols, tile % self.cols) }
+
+    pub fn position_of(&self, tile: usize) -> (usize, usize) {
+        let idx = self.cells.iter().position(|&t| t == tile).expect("Tile is not on the board");
+        (idx / self.cols, idx % self.cols)
+    }
+
+    // The cell of the tile that would slide if mv was played
+    pub fn move_source(&self, mv: Move) -> Option<(usize, usize)> {
+        let (di, dj) = mv.tile_offset();
+        let i = self.blank_cell.0.checked_add_signed(di)?;
+        let j = self.blank_cell.1.checked_add_signed(dj)?;
+        if i < self.rows && j < self.cols {
+            Some((i, j))
+        } else {
+            None
+        }
+    }
+
+    pub fn can_move(&self, mv: Move) -> bool { self.move_source(mv).is_some() }
+
+    pub fn legal_moves(&self) -> Vec<Move> { Move::ALL.into_iter().filter(|&mv| self.can_move(mv)).collect() }
+
+    // The move that slides the tile at cell into the blank, if they are adjacent
+    pub fn move_for_cell(&self, cell: (usize, usize)) -> Option<Move> {
+        Move::ALL.into_iter().find(|&mv| self.move_source(mv) == Some(cell))
+    }
+
+    // The moves that slide every tile from cell up to the blank toward the blank,
+    // nearest first. Empty unless cell is in the blank's row or column.
+    pub fn line_moves(&self, (i, j): (usize, usize)) -> Vec<Move> {
+        let (bi, bj) = self.blank_cell;
+        let (mv, count) = if i == bi && j > bj {
+            (Move::Left, j - bj)
+        } else if i == bi && j < bj {
+            (Move::Right, bj - j)
+        } else if j == bj && i > bi {
+            (Move::Up, i - bi)
+        } else if j == bj && i < bi {
+            (Move::Down, bi - i)
+        } else {
+            return vec![];
+        };
+        vec![mv; count]
+    }
+
+    // The moves that slide every tile between the blank and the edge of the board
+    // in the direction of mv.
+    pub fn line_moves_to_edge(&self, mv: Move) -> Vec<Move> {
+        let (bi, bj) = self.blank_cell;
+        self.line_moves(match mv {
+            Move::Up => (self.rows - 1, bj),
+            Move::Down => (0, bj),
+            Move::Left => (bi, self.cols - 1),
+            Move::Right => (bi, 0),
+        })
+    }
+
+    // Plays a move, returning the cell the tile slid into.
+    pub fn move_tile(&mut self, mv: Move) -> Option<(usize, usize)> { self.move_tiles(&[mv]).first().copied() }
+
+    // Plays moves that are undone and redone together, up to the first one
+    // that can't be played. Returns the cells the tiles slid into.
+    pub fn move_tiles(&mut self, moves: &[Move]) -> Vec<(usize, usize)> {
+        let mut played = vec![];
+        let mut dests = vec![];
+        for &mv in moves {
+            let Some(dest) = self.apply(mv) else { break };
+            played.push(mv);
+            dests.push(dest);
+        }
+        if !played.is_empty() {
+            self.history.push(played);
+            self.redo_stack.clear();
+        }
+        dests
+    }
+
+    // Slides the tile at cell into the blank if they are adjacent
+    pub fn slide_tile(&mut self, cell: (usize, usize)) -> Option<Move> {
+        let mv = self.move_for_cell(cell)?;
+        self.move_tile(mv);
+        Some(mv)
+    }
+
+    // Reverts the moves of the last input, returning the cells the tiles slid
+    // back into in the order they slid. Empty if there's nothing to undo.
+    pub fn undo(&mut self) -> Vec<(usize, usize)> {
+        let Some(moves) = self.history.pop() else { return vec![] };
+        // The inverse of a legal move is always legal
+        let dests = moves.iter().rev().filter_map(|mv| self.apply(mv.inverse())).collect();
+        self.redo_stack.push(moves);
+        dests
+    }
+
+    // Plays the last undone input again, returning the cells the tiles slid into
+    pub fn redo(&mut self) -> Vec<(usize, usize)> {
+        let Some(moves) = self.redo_stack.pop() else { return vec![] };
+        let dests = moves.iter().filter_map(|&mv| self.apply(mv)).collect();
+        self.history.push(moves);
+        dests
+    }
+
+    pub fn is_solved(&self) -> bool { self.cells.iter().enumerate().all(|(idx, &tile)| idx == tile) }
+
+    // Every move swaps the blank with a tile and moves the blank one cell, so the
+    // parity of the permutation always matches the parity of the blank's distance
+    // from home. On boards of at least 2x2 that's enough to be solvable.
+    pub fn is_solvable(&self) -> bool {
+        // Tiles can't pass each other on a single row/column
+        if self.rows < 2 || self.cols < 2 {
+            let tiles: Vec<usize> = self.cells.iter().copied().filter(|&tile| tile != self.blank_tile).collect();
+            return tiles.windows(2).all(|w| w[0] < w[1]);
+        }
+
+        let mut visited = vec![false; self.cells.len()];
+        let mut cycles = 0;
+        for start in 0..self.cells.len() {
+            if !visited[start] {
+                cycles += 1;
+                let mut idx = start;
+                while !visited[idx] {
+                    visited[idx] = true;
+                    idx = self.cells[idx];
+                }
+            }
+        }
+        let permutation_parity = (self.cells.len() - cycles) % 2;
+
+        let (hi, hj) = self.home_of(self.blank_tile);
+        let (bi, bj) = self.blank_cell;
+        permutation_parity == (hi.abs_diff(bi) + hj.abs_diff(bj)) % 2
+    }
+
+    // Sum of every tile's distance from home, ignoring the blank. This never
+    // overestimates the number of moves needed to solve the board.
+    pub fn manhattan_distance(&self) -> u32 {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &tile)| tile != self.blank_tile)
+            .map(|(idx, &tile)| {
+                let (hi, hj) = self.home_of(tile);
+                (hi.abs_diff(idx / self.cols) + hj.abs_diff(idx % self.cols)) as u32
+            })
+            .sum()
+    }
+
+    fn apply(&mut self, mv: Move) -> Option<(usize, usize)> {
+        let (si, sj) = self.move_source(mv)?;
+        let (bi, bj) = self.blank_cell;
+        self.cells.swap(si * self.cols + sj, bi * self.cols + bj);
+        self.blank_cell = (si, sj);
+        Some((bi, bj))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3x3 with the blank in the bottom right corner
+    fn solved() -> Board { Board::new(3, 3, (2, 2)) }
+
+    #[test]
+    fn new_board_is_solved() {
+        let board = solved();
+        assert!(board.is_solved());
+        assert!(board.is_solvable());
+        assert_eq!(board.blank_cell(), (2, 2));
+        assert_eq!(board.tile_at((2, 2)), None);
+        assert_eq!(board.tile_at((0, 1)), Some(1));
+        assert_eq!(board.manhattan_distance(), 0);
+    }
+
+    #[test]
+    fn from_cells_rejects_bad_layouts() {
+        assert!(Board::from_cells(2, 2, vec![0, 1, 2], 3).is_none());
+        assert!(Board::from_cells(2, 2, vec![0, 1, 1, 3], 3).is_none());
+        assert!(Board::from_cells(2, 2, vec![0, 1, 2, 4], 3).is_none());
+        assert!(Board::from_cells(2, 2, vec![0, 1, 2, 3], 4).is_none());
+        assert_eq!(Board::from_cells(2, 2, vec![0, 1, 3, 2], 3).unwrap().blank_cell(), (1, 0));
+    }
+
+    #[test]
+    fn legal_moves_from_a_corner() {
+        let board = solved();
+        assert_eq!(board.legal_moves(), vec![Move::Down, Move::Right]);
+        assert_eq!(board.move_source(Move::Up), None);
+        assert_eq!(board.move_for_cell((1, 2)), Some(Move::Down));
+        assert_eq!(board.move_for_cell((0, 0)), None);
+    }
+
+    #[test]
+    fn slide_moves_the_tile_and_the_blank() {
+        let mut board = solved();
+        assert_eq!(board.slide_tile((2, 1)), Some(Move::Right));
+        assert_eq!(board.blank_cell(), (2, 1));
+        assert_eq!(board.tile_at((2, 2)), Some(7));
+        assert!(!board.is_solved());
+        assert_eq!(board.manhattan_distance(), 1);
+        // Not next to the blank
+        assert_eq!(board.slide_tile((0, 0)), None);
+        assert_eq!(board.history(), [Move::Right]);
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut board = solved();
+        board.slide_tile((2, 1));
+        board.slide_tile((1, 1));
+        let played = board.clone();
+
+        assert_eq!(board.undo(), [(1, 1)]);
+        assert_eq!(board.undo(), [(2, 1)]);
+        assert!(board.is_solved());
+        assert!(board.undo().is_empty());
+
+        assert_eq!(board.redo(), [(2, 2)]);
+        assert_eq!(board.redo(), [(2, 1)]);
+        assert_eq!(board, played);
+        assert!(board.redo().is_empty());
+    }
+
+    #[test]
+    fn a_line_slide_is_undone_and_redone_at_once() {
+        let mut board = solved();
+        assert_eq!(board.move_tiles(&board.line_moves((2, 0))), [(2, 2), (2, 1)]);
+        assert_eq!(board.blank_cell(), (2, 0));
+        let played = board.clone();
+
+        assert_eq!(board.undo(), [(2, 0), (2, 1)]);
+        assert!(board.is_solved());
+        assert!(board.undo().is_empty());
+        assert_eq!(board.redo(), [(2, 2), (2, 1)]);
+        assert_eq!(board, played);
+        assert_eq!(board.history(), [Move::Right, Move::Right]);
+    }
+
+    #[test]
+    fn moves_stop_at_the_first_that_cant_be_played() {
+        let mut board = solved();
+        assert_eq!(board.move_tiles(&[Move::Down, Move::Down, Move::Down]), [(2, 2), (1, 2)]);
+        assert_eq!(board.history(), [Move::Down, Move::Down]);
+        assert!(board.move_tiles(&[Move::Left, Move::Down]).is_empty());
+        assert_eq!(board.undo().len(), 2);
+        assert!(board.is_solved());
+    }
+
+    #[test]
+    fn a_new_move_clears_redo() {
+        let mut board = solved();
+        board.slide_tile((2, 1));
+        board.undo();
+        board.slide_tile((1, 2));
+        assert!(board.redo().is_empty());
+        assert_eq!(board.history(), [Move::Down]);
+    }
+
+    #[test]
+    fn solvable_parity() {
+        // Two tiles swapped can't be solved, swapping two more can
+        let swapped = Board::from_cells(3, 3, vec![1, 0, 2, 3, 4, 5, 6, 7, 8], 8).unwrap();
+        assert!(!swapped.is_solvable());
+        let swapped_twice = Board::from_cells(3, 3, vec![1, 0, 2, 4, 3, 5, 6, 7, 8], 8).unwrap();
+        assert!(swapped_twice.is_solvable());
+
+        // Any sequence of moves keeps a board solvable
+        let mut board = Board::new(3, 4, (0, 0));
+        for mv in [Move::Up, Move::Left, Move::Left, Move::Up, Move::Right, Move::Down] {
+            board.move_tile(mv);
+            assert!(board.is_solvable());
+        }
+
+        // Tiles can't pass each other on a single row
+        assert!(Board::from_cells(1, 3, vec![2, 0, 1], 2).unwrap().is_solvable());
+        assert!(!Board::from_cells(1, 3, vec![1, 0, 2], 2).unwrap().is_solvable());
+    }
+
+    #[test]
+    fn line_moves_toward_the_blank() {
+        let board = solved();
+        assert_eq!(board.line_moves((2, 0)), vec![Move::Right, Move::Right]);
+        assert_eq!(board.line_moves((0, 2)), vec![Move::Down, Move::Down]);
+        assert_eq!(board.line_moves((1, 2)), vec![Move::Down]);
+        // Not in the blank's row or column
+        assert!(board.line_moves((0, 0)).is_empty());
+        assert!(board.line_moves((2, 2)).is_empty());
+        assert_eq!(board.line_moves_to_edge(Move::Right), vec![Move::Right, Move::Right]);
+        assert!(board.line_moves_to_edge(Move::Left).is_empty());
+
+        let mut board = board;
+        for mv in board.line_moves((2, 0)) {
+            board.move_tile(mv);
+        }
+        assert_eq!(board.blank_cell(), (2, 0));
+        assert_eq!(board.cells()[6..], [8, 6, 7]);
+    }
+}
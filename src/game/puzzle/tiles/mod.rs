@@ -1,7 +1,14 @@
+pub mod board;
+pub mod notation;
+pub mod solver;
 pub mod tile;
 pub mod tile_multiplayer;
 pub mod tile_random;
 pub mod tile_state;
 
+pub use board::{Board, Move};
+pub use notation::{format_moves, parse_moves, NotationError};
+pub use solver::{Solution, Solver};
 pub use tile::{Tile, TilePosition};
+pub use tile_random::Scramble;
 pub use tile_state::TileState;
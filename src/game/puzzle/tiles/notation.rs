@@ -0,0 +1,192 @@
+// Text notation for boards and moves, so they can be typed, pasted and shared.
+//
+// Boards are written row by row with rows separated by '/', e.g. "1 2 3/4 0 5/7 8 6".
+// Every tile is numbered by where it belongs in the solved puzzle, counting from 1,
+// and the blank is 0. The number missing from the board is where the blank belongs.
+// Tiles can be separated by spaces or commas.
+//
+// Moves are written as the direction the tile slides, e.g. "ULDR".
+
+use std::{fmt, str::FromStr};
+
+use super::board::{Board, Move};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotationError {
+    Empty,
+    NotANumber(String),
+    // Every row needs as many tiles as the first
+    RaggedRow(usize),
+    // Needs exactly one
+    BlankCount(usize),
+    // The tiles aren't 1 up to the size of the board with one missing
+    BadTiles,
+    BadMove(char),
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotationError::Empty => write!(f, "The board is empty"),
+            NotationError::NotANumber(tile) => write!(f, "\"{}\" is not a tile number", tile),
+            NotationError::RaggedRow(row) => write!(f, "Row {} has the wrong number of tiles", row + 1),
+            NotationError::BlankCount(count) => write!(f, "The board needs one blank (0), found {}", count),
+            NotationError::BadTiles => write!(f, "Tiles must be numbered from 1 to the number of cells without repeats"),
+            NotationError::BadMove(c) => write!(f, "'{}' is not a move, use U, D, L or R", c),
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+impl FromStr for Board {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<Vec<usize>> = s
+            .trim()
+            .split('/')
+            .map(|row| {
+                row.split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|tile| !tile.is_empty())
+                    .map(|tile| tile.parse().map_err(|_| NotationError::NotANumber(tile.to_string())))
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        let cols = rows[0].len();
+        if cols == 0 {
+            return Err(NotationError::Empty);
+        }
+        if let Some(row) = rows.iter().position(|row| row.len() != cols) {
+            return Err(NotationError::RaggedRow(row));
+        }
+
+        let num_rows = rows.len();
+        let labels: Vec<usize> = rows.into_iter().flatten().collect();
+        let blanks = labels.iter().filter(|&&label| label == 0).count();
+        if blanks != 1 {
+            return Err(NotationError::BlankCount(blanks));
+        }
+
+        // The blank belongs wherever the missing number does
+        let mut seen = vec![false; labels.len()];
+        for &label in labels.iter().filter(|&&label| label != 0) {
+            if label > labels.len() || seen[label - 1] {
+                return Err(NotationError::BadTiles);
+            }
+            seen[label - 1] = true;
+        }
+        let blank_tile = seen.iter().position(|&seen| !seen).ok_or(NotationError::BadTiles)?;
+
+        let cells = labels.into_iter().map(|label| if label == 0 { blank_tile } else { label - 1 }).collect();
+        Board::from_cells(num_rows, cols, cells, blank_tile).ok_or(NotationError::BadTiles)
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for i in 0..self.rows() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+            for j in 0..self.cols() {
+                if j > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{}", self.tile_at((i, j)).map_or(0, |tile| tile + 1))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Move {
+    pub fn to_char(self) -> char {
+        match self {
+            Move::Up => 'U',
+            Move::Down => 'D',
+            Move::Left => 'L',
+            Move::Right => 'R',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'U' => Some(Move::Up),
+            'D' => Some(Move::Down),
+            'L' => Some(Move::Left),
+            'R' => Some(Move::Right),
+            _ => None,
+        }
+    }
+}
+
+// Whitespace between moves is ignored
+pub fn parse_moves(s: &str) -> Result<Vec<Move>, NotationError> {
+    s.chars().filter(|c| !c.is_whitespace()).map(|c| Move::from_char(c).ok_or(NotationError::BadMove(c))).collect()
+}
+
+pub fn format_moves(moves: &[Move]) -> String { moves.iter().map(|mv| mv.to_char()).collect() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boards_round_trip() {
+        for notation in ["1 2 3/4 5 6/7 8 0", "1 2 3/4 0 5/7 8 6", "0 2/3 4/5 6", "2 0 3 1"] {
+            let board: Board = notation.parse().unwrap();
+            assert_eq!(board.to_string(), notation);
+            assert_eq!(board.to_string().parse::<Board>().unwrap(), board);
+        }
+    }
+
+    #[test]
+    fn the_missing_number_is_where_the_blank_belongs() {
+        let board: Board = "1 2 3/4 0 5/7 8 6".parse().unwrap();
+        assert_eq!(board.blank_tile(), 8);
+        assert_eq!(board.blank_cell(), (1, 1));
+        // Tile 6 belongs at (1, 2), it's at (2, 2)
+        assert_eq!(board.position_of(5), (2, 2));
+
+        let board: Board = "0 2/3 4/5 6".parse().unwrap();
+        assert_eq!(board.blank_tile(), 0);
+        assert!(board.is_solved());
+    }
+
+    #[test]
+    fn commas_and_extra_spaces_separate_tiles() {
+        let spaced: Board = "  1,2, 3 / 4  5 6/7,8,0 ".parse().unwrap();
+        assert_eq!(spaced, "1 2 3/4 5 6/7 8 0".parse::<Board>().unwrap());
+    }
+
+    #[test]
+    fn bad_boards_say_why() {
+        let error = |notation: &str| notation.parse::<Board>().unwrap_err();
+        assert_eq!(error(""), NotationError::Empty);
+        assert_eq!(error("  /1 2"), NotationError::Empty);
+        assert_eq!(error("1 2 x/4 5 0"), NotationError::NotANumber("x".to_string()));
+        assert_eq!(error("1 2 3/4 0"), NotationError::RaggedRow(1));
+        assert_eq!(error("1 2 3/4 5 6"), NotationError::BlankCount(0));
+        assert_eq!(error("1 0 3/4 5 0"), NotationError::BlankCount(2));
+        assert_eq!(error("1 2 3/4 4 0"), NotationError::BadTiles);
+        assert_eq!(error("1 2 3/4 9 0"), NotationError::BadTiles);
+    }
+
+    #[test]
+    fn moves_round_trip() {
+        let moves = vec![Move::Up, Move::Left, Move::Down, Move::Right, Move::Right];
+        assert_eq!(format_moves(&moves), "ULDRR");
+        assert_eq!(parse_moves(&format_moves(&moves)).unwrap(), moves);
+        // Lowercase and spaces are fine
+        assert_eq!(parse_moves(" u l\nd r R").unwrap(), moves);
+        assert_eq!(parse_moves("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn bad_moves_say_which() {
+        assert_eq!(parse_moves("UDX"), Err(NotationError::BadMove('X')));
+        assert_eq!(parse_moves("U-D"), Err(NotationError::BadMove('-')));
+    }
+}
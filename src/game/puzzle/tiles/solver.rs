@@ -0,0 +1,456 @@
+// Sliding puzzle solver. Boards up to 4x4 are solved optimally with IDA*
+// using the Manhattan distance plus linear conflicts as the heuristic.
+// Anything bigger (or a 4x4 that runs out of nodes) is solved line by line
+// instead, which is quick but not optimal.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use super::board::{Board, Move};
+
+// Largest board (in cells) that gets an optimal solution by default
+const OPTIMAL_MAX_CELLS: usize = 16;
+// Stop reducing the board line by line once it's at most this many rows and columns
+const REDUCED_SIDE_LEN: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub moves: Vec<Move>,
+    pub optimal: bool,
+    // How many boards the search looked at
+    pub nodes: u64,
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Solver {
+    // Give up on an optimal search after this many nodes
+    pub node_limit: Option<u64>,
+}
+
+impl Solver {
+    pub fn new(node_limit: Option<u64>) -> Self { Self { node_limit } }
+
+    // Optimal when the board is small enough and the node limit allows it,
+    // otherwise a fast suboptimal solution. None if the board can't be solved.
+    pub fn solve(&self, board: &Board) -> Option<Solution> {
+        if !board.is_solvable() {
+            return None;
+        }
+        if board.cells().len() <= OPTIMAL_MAX_CELLS {
+            if let Some(solution) = self.solve_optimal(board) {
+                return Some(solution);
+            }
+        }
+        self.solve_fast(board)
+    }
+
+    // The first move of a solution, for hints.
+    pub fn next_move(&self, board: &Board) -> Option<Move> { self.solve(board)?.moves.first().copied() }
+
+    // None if the board can't be solved or the node limit was hit.
+    pub fn solve_optimal(&self, board: &Board) -> Option<Solution> {
+        if !board.is_solvable() {
+            return None;
+        }
+        IdaStar::new(board, self.node_limit).run()
+    }
+
+    // Solves the outer rows and columns one tile at a time until what's left
+    // is small enough to solve optimally. Lines are always taken from the side
+    // away from the blank's home cell, so that cell is in the last bit.
+    pub fn solve_fast(&self, board: &Board) -> Option<Solution> {
+        if !board.is_solvable() {
+            return None;
+        }
+        if board.rows() < 2 || board.cols() < 2 {
+            // There is only ever one way to go
+            return IdaStar::new(board, None).run();
+        }
+
+        let mut board = board.clone();
+        board.clear_history();
+        let cols = board.cols();
+        let (home_i, home_j) = board.home_of(board.blank_tile());
+        let mut free = vec![true; board.cells().len()];
+        let (mut top, mut bottom, mut left, mut right) = (0, board.rows(), 0, board.cols());
+        let mut nodes = 0;
+
+        while bottom - top > REDUCED_SIDE_LEN || right - left > REDUCED_SIDE_LEN {
+            let line: Vec<(usize, usize)> = if bottom - top >= right - left {
+                if home_i != top {
+                    top += 1;
+                    (left..right).map(|j| (top - 1, j)).collect()
+                } else {
+                    bottom -= 1;
+                    (left..right).map(|j| (bottom, j)).collect()
+                }
+            } else if home_j != left {
+                left += 1;
+                (top..bottom).map(|i| (i, left - 1)).collect()
+            } else {
+                right -= 1;
+                (top..bottom).map(|i| (i, right)).collect()
+            };
+
+            // The last two tiles of a line have to go in together
+            let singles = line.len().saturating_sub(2);
+            for &(i, j) in &line[..singles] {
+                nodes += place_tiles(&mut board, &free, &[i * cols + j])?;
+                free[i * cols + j] = false;
+            }
+            let pair: Vec<usize> = line[singles..].iter().map(|&(i, j)| i * cols + j).collect();
+            nodes += place_tiles(&mut board, &free, &pair)?;
+            for idx in pair {
+                free[idx] = false;
+            }
+        }
+
+        // What's left only has tiles that belong in it, so it can be solved on its own
+        let sub_cols = right - left;
+        let sub_cells = (top..bottom)
+            .flat_map(|i| (left..right).map(move |j| (i, j)))
+            .map(|cell| {
+                let (hi, hj) = board.home_of(board.cells()[cell.0 * cols + cell.1]);
+                (hi - top) * sub_cols + (hj - left)
+            })
+            .collect();
+        let sub_board = Board::from_cells(bottom - top, sub_cols, sub_cells, (home_i - top) * sub_cols + (home_j - left))?;
+        let sub_solution = IdaStar::new(&sub_board, None).run()?;
+        for &mv in &sub_solution.moves {
+            board.move_tile(mv);
+        }
+
+        Some(Solution { moves: simplify(&board.history()), optimal: false, nodes: nodes + sub_solution.nodes })
+    }
+}
+
+// Drop any move that is immediately undone by the next one.
+fn simplify(moves: &[Move]) -> Vec<Move> {
+    let mut simplified: Vec<Move> = vec![];
+    for &mv in moves {
+        if simplified.last() == Some(&mv.inverse()) {
+            simplified.pop();
+        } else {
+            simplified.push(mv);
+        }
+    }
+    simplified
+}
+
+// Cell next to idx in the direction the blank travels for mv
+fn blank_destination(idx: usize, rows: usize, cols: usize, mv: Move) -> Option<usize> {
+    let (di, dj) = mv.tile_offset();
+    let i = (idx / cols).checked_add_signed(di)?;
+    let j = (idx % cols).checked_add_signed(dj)?;
+    if i < rows && j < cols {
+        Some(i * cols + j)
+    } else {
+        None
+    }
+}
+
+fn distance(a: usize, b: usize, cols: usize) -> u32 { ((a / cols).abs_diff(b / cols) + (a % cols).abs_diff(b % cols)) as u32 }
+
+// Moves the tiles that belong in the given cells home, only moving the blank
+// through free cells and ignoring where every other tile ends up.
+// Returns the number of nodes searched.
+fn place_tiles(board: &mut Board, free: &[bool], homes: &[usize]) -> Option<u64> {
+    let (rows, cols) = (board.rows(), board.cols());
+    let blank = board.blank_cell().0 * cols + board.blank_cell().1;
+    let tiles: Vec<usize> = homes.iter().map(|&home| board.cells().iter().position(|&t| t == home).unwrap()).collect();
+
+    // Tile distances, plus getting the blank next to a tile that still needs to move
+    let heuristic = |(blank, tiles): &(usize, Vec<usize>)| -> u32 {
+        let tile_distances: u32 = tiles.iter().zip(homes).map(|(&tile, &home)| distance(tile, home, cols)).sum();
+        let blank_distance =
+            tiles.iter().zip(homes).filter(|(tile, home)| tile != home).map(|(&tile, _)| distance(*blank, tile, cols) - 1).min();
+        tile_distances + blank_distance.unwrap_or(0)
+    };
+
+    let start = (blank, tiles);
+    let mut parents: HashMap<(usize, Vec<usize>), Option<((usize, Vec<usize>), Move)>> = HashMap::from([(start.clone(), None)]);
+    let mut costs = HashMap::from([(start.clone(), 0)]);
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0, start))]);
+    let mut nodes = 0;
+
+    while let Some(Reverse((_, g, state))) = queue.pop() {
+        if costs[&state] < g {
+            continue;
+        }
+        nodes += 1;
+        if state.1.iter().zip(homes).all(|(tile, home)| tile == home) {
+            let mut moves = vec![];
+            let mut current = state;
+            while let Some((parent, mv)) = parents[&current].clone() {
+                moves.push(mv);
+                current = parent;
+            }
+            for &mv in moves.iter().rev() {
+                board.move_tile(mv);
+            }
+            return Some(nodes);
+        }
+
+        let (blank, tiles) = &state;
+        for mv in Move::ALL {
+            let Some(next_blank) = blank_destination(*blank, rows, cols, mv) else { continue };
+            if !free[next_blank] {
+                continue;
+            }
+            let next_tiles = tiles.iter().map(|&tile| if tile == next_blank { *blank } else { tile }).collect();
+            let next = (next_blank, next_tiles);
+            if costs.get(&next).is_none_or(|&cost| g + 1 < cost) {
+                costs.insert(next.clone(), g + 1);
+                parents.insert(next.clone(), Some((state.clone(), mv)));
+                queue.push(Reverse((g + 1 + heuristic(&next), g + 1, next)));
+            }
+        }
+    }
+    None
+}
+
+enum SearchResult {
+    Found,
+    // The smallest cost that went over the bound
+    Exceeded(u32),
+    NodeLimit,
+}
+
+// Iterative deepening A*. The Manhattan distance and the linear conflicts of
+// every row and column are updated incrementally as moves are made.
+struct IdaStar {
+    rows: usize,
+    cols: usize,
+    cells: Vec<usize>,
+    blank: usize,
+    blank_tile: usize,
+
+    manhattan: u32,
+    row_conflicts: Vec<u32>,
+    col_conflicts: Vec<u32>,
+    total_conflicts: u32,
+
+    path: Vec<Move>,
+    nodes: u64,
+    node_limit: Option<u64>,
+}
+
+impl IdaStar {
+    fn new(board: &Board, node_limit: Option<u64>) -> Self {
+        let (rows, cols) = (board.rows(), board.cols());
+        let mut search = Self {
+            rows,
+            cols,
+            cells: board.cells().to_vec(),
+            blank: board.blank_cell().0 * cols + board.blank_cell().1,
+            blank_tile: board.blank_tile(),
+            manhattan: board.manhattan_distance(),
+            row_conflicts: vec![0; rows],
+            col_conflicts: vec![0; cols],
+            total_conflicts: 0,
+            path: vec![],
+            nodes: 0,
+            node_limit,
+        };
+        search.row_conflicts = (0..rows).map(|i| search.line_conflicts(i, true)).collect();
+        search.col_conflicts = (0..cols).map(|j| search.line_conflicts(j, false)).collect();
+        search.total_conflicts = search.row_conflicts.iter().chain(&search.col_conflicts).sum();
+        search
+    }
+
+    fn heuristic(&self) -> u32 { self.manhattan + 2 * self.total_conflicts }
+
+    // Tiles in a line that belong in that line but are in the wrong order.
+    // Every one of them has to leave the line and come back, which costs two
+    // moves on top of the Manhattan distance.
+    fn line_conflicts(&self, line: usize, row: bool) -> u32 {
+        let cols = self.cols;
+        let len = if row { self.cols } else { self.rows };
+        let goals: Vec<usize> = (0..len)
+            .map(|k| if row { self.cells[line * cols + k] } else { self.cells[k * cols + line] })
+            .filter(|&tile| tile != self.blank_tile)
+            .filter(|&tile| if row { tile / cols == line } else { tile % cols == line })
+            .map(|tile| if row { tile % cols } else { tile / cols })
+            .collect();
+
+        // The tiles in the longest increasing subsequence can stay
+        let mut longest = vec![1; goals.len()];
+        for a in 0..goals.len() {
+            for b in 0..a {
+                if goals[b] < goals[a] {
+                    longest[a] = longest[a].max(longest[b] + 1);
+                }
+            }
+        }
+        (goals.len() - longest.into_iter().max().unwrap_or(0)) as u32
+    }
+
+    // Slides the tile at src into the blank, updating the heuristic
+    fn slide(&mut self, src: usize) {
+        let dest = self.blank;
+        let tile = self.cells[src];
+        self.manhattan = self.manhattan + distance(tile, dest, self.cols) - distance(tile, src, self.cols);
+        self.cells.swap(src, dest);
+        self.blank = src;
+
+        // Moving along a line doesn't change the order of the tiles in it,
+        // only which rows (or columns) the tile is in.
+        let vertical = src / self.cols != dest / self.cols;
+        for line in [src, dest].map(|idx| if vertical { idx / self.cols } else { idx % self.cols }) {
+            let conflicts = self.line_conflicts(line, vertical);
+            let old = if vertical { &mut self.row_conflicts[line] } else { &mut self.col_conflicts[line] };
+            self.total_conflicts = self.total_conflicts + conflicts - *old;
+            *old = conflicts;
+        }
+    }
+
+    fn search(&mut self, g: u32, bound: u32) -> SearchResult {
+        self.nodes += 1;
+        if self.node_limit.is_some_and(|limit| self.nodes > limit) {
+            return SearchResult::NodeLimit;
+        }
+
+        let f = g + self.heuristic();
+        if f > bound {
+            return SearchResult::Exceeded(f);
+        }
+        if self.manhattan == 0 {
+            return SearchResult::Found;
+        }
+
+        let mut min_exceeded = u32::MAX;
+        for mv in Move::ALL {
+            if self.path.last() == Some(&mv.inverse()) {
+                continue;
+            }
+            let Some(src) = blank_destination(self.blank, self.rows, self.cols, mv) else { continue };
+
+            let dest = self.blank;
+            self.slide(src);
+            self.path.push(mv);
+            match self.search(g + 1, bound) {
+                SearchResult::Found => return SearchResult::Found,
+                SearchResult::NodeLimit => return SearchResult::NodeLimit,
+                SearchResult::Exceeded(f) => min_exceeded = min_exceeded.min(f),
+            }
+            self.path.pop();
+            self.slide(dest);
+        }
+        SearchResult::Exceeded(min_exceeded)
+    }
+
+    fn run(mut self) -> Option<Solution> {
+        let mut bound = self.heuristic();
+        loop {
+            match self.search(0, bound) {
+                SearchResult::Found => return Some(Solution { moves: self.path, optimal: true, nodes: self.nodes }),
+                SearchResult::NodeLimit => return None,
+                // Nothing left to search, shouldn't happen on a solvable board
+                SearchResult::Exceeded(u32::MAX) => return None,
+                SearchResult::Exceeded(next_bound) => bound = next_bound,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::game::puzzle::tiles::{tile_random::TileRandom, Scramble};
+
+    fn solves(board: &Board, solution: &Solution) -> bool {
+        let mut board = board.clone();
+        solution.moves.iter().all(|&mv| board.move_tile(mv).is_some()) && board.is_solved()
+    }
+
+    // Every board reachable from the solved one, with how many moves it takes
+    fn all_distances(rows: usize, cols: usize) -> HashMap<Vec<usize>, u32> {
+        let solved = Board::new(rows, cols, (rows - 1, cols - 1));
+        let mut distances = HashMap::from([(solved.cells().to_vec(), 0)]);
+        let mut queue = VecDeque::from([solved]);
+        while let Some(board) = queue.pop_front() {
+            let distance = distances[board.cells()];
+            for mv in board.legal_moves() {
+                let mut next = board.clone();
+                next.move_tile(mv);
+                if !distances.contains_key(next.cells()) {
+                    distances.insert(next.cells().to_vec(), distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    #[test]
+    fn optimal_on_every_small_board() {
+        for (rows, cols) in [(2, 3), (3, 2), (2, 2)] {
+            for (cells, distance) in all_distances(rows, cols) {
+                let board = Board::from_cells(rows, cols, cells, rows * cols - 1).unwrap();
+                let solution = Solver::default().solve_optimal(&board).unwrap();
+                assert_eq!(solution.moves.len() as u32, distance, "{}", board);
+                assert!(solves(&board, &solution));
+                assert!(IdaStar::new(&board, None).heuristic() <= distance, "{}", board);
+            }
+        }
+    }
+
+    #[test]
+    fn known_optimal_lengths() {
+        assert_eq!(Solver::default().solve_optimal(&Board::new(3, 3, (2, 2))).unwrap().moves.len(), 0);
+
+        let mut one_away = Board::new(3, 3, (2, 2));
+        one_away.move_tile(Move::Down);
+        assert_eq!(Solver::default().solve(&one_away).unwrap().moves, vec![Move::Up]);
+
+        // One of the two hardest 3x3 boards, 8 6 7 / 2 5 4 / 3 _ 1
+        let hardest = Board::from_cells(3, 3, vec![7, 5, 6, 1, 4, 3, 2, 8, 0], 8).unwrap();
+        let solution = Solver::default().solve(&hardest).unwrap();
+        assert!(solution.optimal);
+        assert_eq!(solution.moves.len(), 31);
+        assert!(solves(&hardest, &solution));
+    }
+
+    #[test]
+    fn heuristic_is_admissible() {
+        // Against the real distance of every 3x3 board
+        for (cells, distance) in all_distances(3, 3) {
+            let board = Board::from_cells(3, 3, cells, 8).unwrap();
+            let search = IdaStar::new(&board, None);
+            assert!(search.manhattan <= search.heuristic());
+            assert!(search.heuristic() <= distance, "{}", board);
+        }
+
+        // And against the optimal solution of random 3x4 boards
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..5 {
+            let mut board = Board::new(3, 4, (2, 3));
+            TileRandom::scramble(&mut board, Scramble::MinMoves(20), &mut rng);
+            let solution = Solver::default().solve_optimal(&board).unwrap();
+            assert!(IdaStar::new(&board, None).heuristic() as usize <= solution.moves.len(), "{}", board);
+            assert!(solves(&board, &solution));
+        }
+    }
+
+    #[test]
+    fn unsolvable_boards_have_no_solution() {
+        let swapped = Board::from_cells(3, 3, vec![1, 0, 2, 3, 4, 5, 6, 7, 8], 8).unwrap();
+        assert!(Solver::default().solve(&swapped).is_none());
+    }
+
+    #[test]
+    fn fast_solution_solves_big_boards() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut board = Board::new(6, 5, (0, 0));
+        TileRandom::scramble(&mut board, Scramble::Random, &mut rng);
+        let solution = Solver::default().solve(&board).unwrap();
+        assert!(!solution.optimal);
+        assert!(solves(&board, &solution));
+    }
+}
@@ -10,7 +10,7 @@ use crate::game::{
     drawable::Drawable,
 };
 
-const TILE_GAP: f32 = 20.0;
+pub const TILE_GAP: f32 = 20.0;
 
 pub const TILE_PADDING_X: f32 = 90.0;
 pub const TILE_PADDING_Y: f32 = 150.0;
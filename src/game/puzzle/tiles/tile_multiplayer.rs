@@ -6,8 +6,11 @@ use log::trace;
 use crate::game::{
     multiplayer::{transport::MultiplayerTransport, MultiplayerGameMessage},
     player::PuzzleStatistics,
+    ui::error_toast,
 };
 
+use super::board::Board;
+
 #[derive(Default)]
 pub struct TileMultiplayerTransport {
     transport: Option<Arc<MultiplayerTransport>>,
@@ -16,6 +19,8 @@ pub struct TileMultiplayerTransport {
 impl TileMultiplayerTransport {
     pub fn new(transport: Option<Arc<MultiplayerTransport>>) -> Self { Self { transport } }
 
+    pub fn is_multiplayer(&self) -> bool { self.transport.is_some() }
+
     pub fn delete_random_tile(&mut self, tile: (usize, usize)) -> GameResult {
         if let Some(t) = &self.transport {
             t.event_push_buffer
@@ -25,13 +30,20 @@ impl TileMultiplayerTransport {
         Ok(())
     }
 
+    pub fn set_board(&mut self, board: &Board) -> GameResult {
+        if let Some(t) = &self.transport {
+            t.event_push_buffer
+                .send(MultiplayerGameMessage::SetBoard(board.clone()))
+                .map_err(|_| GameError::CustomError("Failed to send scrambled board to peer".to_string()))?;
+        }
+        Ok(())
+    }
+
     pub fn swap_tiles(&mut self, i1j1: (usize, usize), i2j2: (usize, usize), duration: f32) {
         if let Some(transport) = &self.transport {
-            // TODO handle this expect better
-            transport
-                .event_push_buffer
-                .send(MultiplayerGameMessage::SwapTiles { i1j1, i2j2, duration })
-                .expect("Failed to push tile swap into transport");
+            if transport.event_push_buffer.send(MultiplayerGameMessage::SwapTiles { i1j1, i2j2, duration }).is_err() {
+                error_toast::report("Couldn't send the move, the connection was lost".to_string());
+            }
         }
     }
 
@@ -51,11 +63,9 @@ impl TileMultiplayerTransport {
 
     pub fn end_game(&mut self, stats: PuzzleStatistics) {
         if let Some(transport) = &self.transport {
-            // TODO handle this expect better
-            transport
-                .event_push_buffer
-                .send(MultiplayerGameMessage::GameCompleted(stats))
-                .expect("Failed to push game completed in transport");
+            if transport.event_push_buffer.send(MultiplayerGameMessage::GameCompleted(stats)).is_err() {
+                error_toast::report("Couldn't tell the other player you finished, the connection was lost".to_string());
+            }
         }
     }
 }
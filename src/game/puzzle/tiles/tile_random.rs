@@ -1,66 +1,108 @@
-use rand::Rng;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::board::{Board, Move};
+
+// Gives up on reaching the target difficulty after this many moves per tile.
+const MAX_WALK_MOVES_PER_TILE: usize = 1000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Scramble {
+    // Any solvable arrangement, all equally likely
+    #[default]
+    Random,
+    // An arrangement that takes at least this many moves to solve
+    MinMoves(u32),
+}
 
 pub struct TileRandom {}
 impl TileRandom {
-    pub fn random_adjacent_tile(blank_cell: (usize, usize), col_cnt_tiles: usize, row_cnt_tiles: usize) -> (usize, usize) {
-        // TODO make this static, or something
-        let mut rng = rand::thread_rng();
-
-        let replacetile = if blank_cell == (0, 0) {
-            rng.gen_range(0..2) as usize
-        } else if blank_cell == (0, col_cnt_tiles as usize - 1) {
-            let r = rng.gen_range(0..2);
-            (if r == 1 { 2 } else { r } as usize)
-        } else if blank_cell == (row_cnt_tiles as usize - 1, col_cnt_tiles as usize - 1) {
-            let r = rng.gen_range(0..2);
-            (if r == 0 { 3 } else { 2 } as usize)
-        } else if blank_cell == (row_cnt_tiles as usize - 1, 0) {
-            let r = rng.gen_range(0..2);
-            (if r == 0 { 3 } else { r } as usize)
-        }
-        // Left edge
-        else if blank_cell.1 == 0 {
-            let r = rng.gen_range(0..3);
-            (if r == 2 { 3 } else { r } as usize)
+    // Returns the board's Manhattan distance, which can be below MinMoves if
+    // the walk gave up first
+    pub fn scramble<R: Rng>(board: &mut Board, scramble: Scramble, rng: &mut R) -> u32 {
+        match scramble {
+            // A 1xN board can only shuffle the blank along its line, so parity alone
+            // doesn't tell us what's reachable.
+            Scramble::Random if board.rows() < 2 || board.cols() < 2 => Self::random_walk(board, u32::MAX, rng),
+            Scramble::Random => Self::random_permutation(board, rng),
+            Scramble::MinMoves(min_moves) => Self::random_walk(board, min_moves.max(1), rng),
         }
-        // Top edge
-        else if blank_cell.0 == 0 {
-            rng.gen_range(0..3)
-        }
-        // Right edge
-        else if blank_cell.1 == col_cnt_tiles as usize - 1 {
-            let r = rng.gen_range(0..3);
-            (if r == 1 { 3 } else { r } as usize)
-        }
-        // Bottom edge
-        else if blank_cell.0 == row_cnt_tiles as usize - 1 {
-            rng.gen_range(0..3) + 1
-        } else {
-            rng.gen_range(0..4) as usize
-        };
+        board.clear_history();
+        board.manhattan_distance()
+    }
 
-        let (c1, c2) = blank_cell;
+    // No rows x cols board has a larger Manhattan distance than every tile being
+    // mirrored through the center, so a MinMoves above this can't be reached.
+    pub fn max_min_moves(rows: usize, cols: usize) -> u32 {
+        (0..rows * cols)
+            .map(|idx| ((idx / cols).abs_diff(rows - 1 - idx / cols) + (idx % cols).abs_diff(cols - 1 - idx % cols)) as u32)
+            .sum()
+    }
 
-        // println!("swap {:?} {:?}", blank_cell, replacetile);
-        let tile2 = match replacetile {
-            0 => {
-                // Down
-                (c1 + 1, c2)
+    // Shuffles every tile, then swaps two tiles if the result can't be solved.
+    fn random_permutation<R: Rng>(board: &mut Board, rng: &mut R) {
+        let mut cells = board.cells().to_vec();
+        loop {
+            cells.shuffle(rng);
+            let mut shuffled = Board::from_cells(board.rows(), board.cols(), cells.clone(), board.blank_tile()).unwrap();
+            if !shuffled.is_solvable() {
+                let mut non_blank = (0..cells.len()).filter(|&idx| cells[idx] != board.blank_tile());
+                let (a, b) = (non_blank.next().unwrap(), non_blank.next().unwrap());
+                cells.swap(a, b);
+                shuffled = Board::from_cells(board.rows(), board.cols(), cells.clone(), board.blank_tile()).unwrap();
             }
-            1 => {
-                // Right
-                (c1, c2 + 1)
+            if !shuffled.is_solved() {
+                *board = shuffled;
+                return;
             }
-            2 => {
-                // Left
-                (c1, c2 - 1)
+        }
+    }
+
+    // Slides random tiles (never straight back) until the Manhattan distance,
+    // which never overestimates the moves needed, reaches min_moves.
+    fn random_walk<R: Rng>(board: &mut Board, min_moves: u32, rng: &mut R) {
+        let mut previous: Option<Move> = None;
+        for _ in 0..board.cells().len() * MAX_WALK_MOVES_PER_TILE {
+            if board.manhattan_distance() >= min_moves {
+                return;
             }
-            3 => {
-                // Up
-                (c1 - 1, c2)
+            let mut moves: Vec<Move> = board.legal_moves().into_iter().filter(|&mv| Some(mv.inverse()) != previous).collect();
+            // Dead end on a single row/column board
+            if moves.is_empty() {
+                moves = board.legal_moves();
             }
-            _ => panic!("Should never happen"),
-        };
-        tile2
+            let Some(&mv) = moves.choose(rng) else { return };
+            board.move_tile(mv);
+            previous = Some(mv);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn max_min_moves_mirrors_every_tile() {
+        assert_eq!(TileRandom::max_min_moves(3, 3), 24);
+        assert_eq!(TileRandom::max_min_moves(1, 3), 4);
+    }
+
+    #[test]
+    fn scramble_reports_the_difficulty_reached() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut board = Board::new(3, 3, (2, 2));
+        let reached = TileRandom::scramble(&mut board, Scramble::MinMoves(10), &mut rng);
+        assert!(reached >= 10);
+        assert_eq!(reached, board.manhattan_distance());
+        assert!(board.is_solvable());
+        assert!(board.history().is_empty());
+
+        // Out of reach, the board says how far it got instead
+        let mut board = Board::new(3, 3, (2, 2));
+        let reached = TileRandom::scramble(&mut board, Scramble::MinMoves(1000), &mut rng);
+        assert!(reached <= TileRandom::max_min_moves(3, 3));
     }
 }
@@ -1,13 +1,14 @@
 // TODO move animation code to tile_animation.rs
 
 use chrono::Local;
-use image::{imageops::FilterType, io::Reader as ImageReader, GenericImageView, Pixel};
+use image::{GenericImageView, Pixel};
 
-use rand::Rng;
-use std::{cell::RefCell, io::BufReader, rc::Rc};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{cell::RefCell, rc::Rc};
 
 use ggez::{
-    graphics::{Canvas, Image, ImageFormat},
+    glam::Vec2,
+    graphics::{Canvas, Image, ImageFormat, Rect},
     timer::TimeContext,
     Context, GameResult,
 };
@@ -18,23 +19,33 @@ use crate::game::{
         animation::{Animation, AnimationData},
     },
     drawable::Drawable,
-    input::InputAction,
-    player::{PuzzleStatistics, PLAYER},
-    puzzle::puzzle_listing::PuzzleListing,
+    input::{hint_overlay::HintOverlay, mouse::MouseAction, InputAction},
+    player::{GameMode, PuzzleStatistics, DEFAULT_AUTO_SOLVE_SPEED, PLAYER},
+    puzzle::{puzzle_listing::PuzzleListing, replay::Replay},
+    resources::image_loader::{ImageLoader, PuzzleId},
     scene::Scene,
+    ui::{
+        clipboard,
+        error_scene::{main_menu, try_scene},
+        error_toast,
+    },
 };
 
 #[cfg(feature = "multiplayer")]
 use crate::game::multiplayer::MultiplayerGameMessage;
 
 use super::{
-    tile::{TILE_PADDING_X, TILE_PADDING_Y},
+    board::{Board, Move},
+    solver::Solver,
+    tile::{TILE_GAP, TILE_PADDING_X, TILE_PADDING_Y},
     tile_multiplayer::TileMultiplayerTransport,
-    tile_random::TileRandom,
+    tile_random::{Scramble, TileRandom},
     Tile, TilePosition,
 };
 
+#[derive(Default)]
 enum GameStage {
+    #[default]
     StartingAnimation,
     Started,
     FinishingAnimation,
@@ -42,29 +53,34 @@ enum GameStage {
     Cancelled,
 }
 
-impl Default for GameStage {
-    fn default() -> Self { Self::StartingAnimation }
-}
-
 // TODO: Add tile scale animation when the game is finished.
 
-const TOTAL_SCRAMBLE_SWAPS: u32 = 50;
 const IMAGE_SIDELEN: u32 = 600;
-const TILE_SLIDE_DURATION: f32 = 0.3;
+pub const TILE_SLIDE_DURATION: f32 = 0.3;
+// Keeps hints and auto-solve from freezing the game on bigger boards, the
+// solver falls back to a quicker (but not optimal) solution past this.
+const SOLVER_NODE_LIMIT: u64 = 200_000;
+// How long auto-solve takes per move at speed 1
+const AUTO_SOLVE_SLIDE_DURATION: f32 = TILE_SLIDE_DURATION * DEFAULT_AUTO_SOLVE_SPEED as f32;
 
 #[derive(Default)]
 pub struct TileState {
+    // Indexed by where each tile belongs in the solved puzzle
     pub tiles: Vec<Vec<Rc<RefCell<Tile>>>>,
-    pub ref_board: Vec<Vec<Option<Rc<RefCell<Tile>>>>>,
-    // For efficiency purposes
-    pub blank_cell: (usize, usize),
-    pub tile_blank_cell: (usize, usize),
-
-    previous_swap: Option<(usize, usize)>,
+    pub board: Board,
 
     animation: Animation<TilePosition>,
-    img_num: usize,
+    puzzle_id: PuzzleId,
     total_moves: u32,
+    total_multi_tile_moves: u32,
+    hints_used: u32,
+    hint: Option<HintOverlay>,
+    // The game solved the puzzle, so it's kept apart from the player's games
+    auto_solved: bool,
+    // Only the local player's game is recorded
+    replay: Option<Replay>,
+    // What the board was scrambled from, None for custom boards and the peer
+    scramble_seed: Option<u64>,
     timer: Option<TimeContext>,
 
     game_stage: GameStage,
@@ -79,11 +95,64 @@ pub struct TileState {
 }
 
 impl TileState {
-    pub fn new_singleplayer(context: &mut Context, img_num: usize, num_rows_cols: usize, xy: (f32, f32)) -> GameResult<Self> {
-        Self::new(context, img_num, num_rows_cols, xy, TileMultiplayerTransport::new(None), false)
+    pub fn new_singleplayer(
+        context: &mut Context, puzzle_id: &PuzzleId, num_rows_cols: (usize, usize), scramble: Scramble, xy: (f32, f32),
+    ) -> GameResult<Self> {
+        Self::new(context, puzzle_id, num_rows_cols, scramble, xy, TileMultiplayerTransport::new(None), false)
     }
     pub fn new(
-        context: &mut Context, img_num: usize, num_rows_cols: usize, (x, y): (f32, f32), transport: TileMultiplayerTransport, peer: bool,
+        context: &mut Context, puzzle_id: &PuzzleId, num_rows_cols: (usize, usize), scramble: Scramble, xy: (f32, f32),
+        transport: TileMultiplayerTransport, peer: bool,
+    ) -> GameResult<Self> {
+        let mut tile_state = Self::new_unscrambled(context, puzzle_id, num_rows_cols, xy, transport, peer)?;
+
+        // Remove one random tile from the board, then scramble the rest.
+        // The peer gets both from the other player instead. This goes in the
+        // same order as the scramble command, so the seed gives the same board.
+        if !tile_state.peer {
+            // Kept to 63 bits so it fits in a TOML profile export
+            let seed: u64 = rand::random::<u64>() >> 1;
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (rows, cols) = num_rows_cols;
+            tile_state.delete_random_tile(Some((rng.gen_range(0..rows), rng.gen_range(0..cols))))?;
+            let mut board = tile_state.board.clone();
+            let reached = TileRandom::scramble(&mut board, scramble, &mut rng);
+            if let Scramble::MinMoves(min_moves) = scramble {
+                if reached < min_moves {
+                    error_toast::report(format!("Couldn't scramble to {} moves, this board takes at least {}", min_moves, reached));
+                }
+            }
+            tile_state.scramble_seed = Some(seed);
+            tile_state.replay = Some(Replay::new(puzzle_id.clone(), board.clone()));
+            tile_state.set_board(board)?;
+        }
+
+        Ok(tile_state)
+    }
+
+    // A singleplayer game that starts from the given board instead of a scramble
+    pub fn new_custom(context: &mut Context, puzzle_id: &PuzzleId, board: &Board, xy: (f32, f32)) -> GameResult<Self> {
+        let mut tile_state =
+            Self::new_unscrambled(context, puzzle_id, (board.rows(), board.cols()), xy, TileMultiplayerTransport::new(None), false)?;
+        tile_state.delete_random_tile(Some(board.home_of(board.blank_tile())))?;
+        tile_state.replay = Some(Replay::new(puzzle_id.clone(), board.clone()));
+        tile_state.set_board(board.clone())?;
+        Ok(tile_state)
+    }
+
+    // Shows a board without scrambling it. Like a peer, it only moves when told to.
+    pub fn new_replay(context: &mut Context, puzzle_id: &PuzzleId, board: &Board, xy: (f32, f32)) -> GameResult<Self> {
+        let mut tile_state =
+            Self::new_unscrambled(context, puzzle_id, (board.rows(), board.cols()), xy, TileMultiplayerTransport::new(None), true)?;
+        tile_state.delete_random_tile(Some(board.home_of(board.blank_tile())))?;
+        tile_state.set_board(board.clone())?;
+        Ok(tile_state)
+    }
+
+    // Cuts the image into tiles, all of them in their solved positions
+    fn new_unscrambled(
+        context: &mut Context, puzzle_id: &PuzzleId, (row_cnt_tiles, col_cnt_tiles): (usize, usize), (x, y): (f32, f32),
+        transport: TileMultiplayerTransport, peer: bool,
     ) -> GameResult<Self> {
         // Peer determines whether or not a game is multiplayer
         /* Cannot use ImageLoader here b/c of this error in to_pixels ---
@@ -97,23 +166,24 @@ impl TileState {
         ', /home/nonuser/.cargo/registry/src/github.com-1ecc6299db9ec823/wgpu-0.14.2/src/backend/direct.rs:2403:5
                 */
 
-        let mut img = ImageReader::new(BufReader::new(context.fs.open(format!("/images/{}.jpg", img_num))?));
-        img.set_format(image::ImageFormat::Jpeg);
-
-        let img = img.decode().expect("failed to open image").resize(IMAGE_SIDELEN, IMAGE_SIDELEN, FilterType::Lanczos3);
+        let img = ImageLoader::full_image(context, puzzle_id)?;
 
-        // How many tiles in a row? In a column?
-        let col_cnt_tiles = num_rows_cols; // img.width() / tile_size;
-        let row_cnt_tiles = num_rows_cols; // img.height() / tile_size;
+        // Tiles are square, so the longer side of the board decides how big they are.
+        // An image that isn't the shape of the board is cropped or letterboxed.
+        let longest_side = row_cnt_tiles.max(col_cnt_tiles);
+        let tile_size: u32 = IMAGE_SIDELEN / longest_side as u32;
+        let img = ImageLoader::fit().apply(&img, tile_size * col_cnt_tiles as u32, tile_size * row_cnt_tiles as u32);
 
-        let tile_size: u32 = img.width() / num_rows_cols as u32;
+        // Center the shorter side of the board in the space a square board would take
+        let x = x + ((longest_side - col_cnt_tiles) as f32 * (tile_size as f32 + TILE_GAP)) / 2.0;
+        let y = y + ((longest_side - row_cnt_tiles) as f32 * (tile_size as f32 + TILE_GAP)) / 2.0;
 
         // Use Default for this
         let mut tile_state = Self {
-            ref_board: vec![vec![None; col_cnt_tiles as usize]; row_cnt_tiles as usize],
+            board: Board::new(row_cnt_tiles, col_cnt_tiles, (0, 0)),
             transport,
             peer,
-            img_num,
+            puzzle_id: puzzle_id.clone(),
             x,
             y,
             ..Default::default()
@@ -160,23 +230,6 @@ impl TileState {
             tile_state.tiles.push(tile_row);
         }
 
-        // Copy generated tile board to ref board
-        for i in 0..row_cnt_tiles {
-            for j in 0..col_cnt_tiles {
-                let i = i as usize;
-                let j = j as usize;
-                tile_state.ref_board[i][j] = Some(tile_state.tiles[i][j].clone());
-            }
-        }
-
-        // Remove one random tile from ref board.
-        if !tile_state.peer {
-            tile_state.delete_random_tile(None)?;
-            for _ in 0..TOTAL_SCRAMBLE_SWAPS {
-                tile_state.swap_random_tile_blank();
-            }
-        }
-
         tile_state.timer = Some(TimeContext::new());
 
         Ok(tile_state)
@@ -187,74 +240,176 @@ impl TileState {
             peer_tile
         } else {
             let mut rng = rand::thread_rng();
-            (rng.gen_range(0..self.tiles.len()) as usize, rng.gen_range(0..self.tiles.len()) as usize)
+            (rng.gen_range(0..self.board.rows()), rng.gen_range(0..self.board.cols()))
         };
-        println!("deleting {:?} from ref board", (i, j));
+        log::debug!("Deleting {:?} from the board", (i, j));
 
         self.animation.push_seq(AnimationData::Unsimultaneous);
         self.animation.push_seq(AnimationData::Generator((
-            self.ref_board[i][j].as_ref().unwrap().clone(),
+            self.tiles[i][j].clone(),
             {
-                let mut x =
-                    TilePosition::from_ij_no_gap(i, j, self.ref_board[i][j].as_ref().unwrap().as_ref().borrow().side_len, self.x, self.y);
+                let mut x = TilePosition::from_ij_no_gap(i, j, self.tiles[i][j].as_ref().borrow().side_len, self.x, self.y);
                 x.scale = 0.0;
                 x
             },
             TILE_SLIDE_DURATION * 4.0,
         )));
-        self.ref_board[i][j] = None;
-        self.tile_blank_cell = (i, j);
-        self.blank_cell = (i, j);
+        self.board = Board::new(self.board.rows(), self.board.cols(), (i, j));
 
         if !self.peer {
-            self.transport.delete_random_tile(self.blank_cell)?;
+            self.transport.delete_random_tile(self.board.blank_cell())?;
         }
         Ok(())
     }
 
-    pub fn finished(&self) -> bool {
-        if let GameStage::Finished = self.game_stage {
-            true
-        } else {
-            false
+    pub fn started(&self) -> bool { matches!(self.game_stage, GameStage::Started) }
+    pub fn finished(&self) -> bool { matches!(self.game_stage, GameStage::Finished) }
+
+    // Slides the tile at (i2, j2) into the blank cell at (i1, j1)
+    pub fn swap_ref_tiles(&mut self, (i1, j1): (usize, usize), (i2, j2): (usize, usize), duration: f32) {
+        if self.board.blank_cell() != (i1, j1) || self.board.slide_tile((i2, j2)).is_none() {
+            log::warn!("Ignoring invalid swap {:?} {:?}", (i1, j1), (i2, j2));
+            return;
         }
+        self.tile_slid((i1, j1), (i2, j2), duration);
     }
 
-    pub fn swap_ref_tiles(&mut self, (i1, j1): (usize, usize), (i2, j2): (usize, usize), duration: f32) {
+    // Both return how many tiles slid, the last input's tiles go back together
+    pub fn undo_move(&mut self) -> u32 {
+        let dests = self.board.undo();
+        self.tiles_slid_together(&dests);
+        dests.len() as u32
+    }
+
+    pub fn redo_move(&mut self) -> u32 {
+        let dests = self.board.redo();
+        self.tiles_slid_together(&dests);
+        dests.len() as u32
+    }
+
+    // Called once the board has slid a line of tiles into dests, in order. Each
+    // tile came from where the one after it went.
+    fn tiles_slid_together(&mut self, dests: &[(usize, usize)]) {
+        if dests.is_empty() {
+            return;
+        }
+        let froms = dests.iter().skip(1).copied().chain([self.board.blank_cell()]);
+        self.animation.push_seq(AnimationData::Simultaneous);
+        for (&to, from) in dests.iter().zip(froms) {
+            self.tile_slid(to, from, TILE_SLIDE_DURATION);
+        }
+        self.animation.push_seq(AnimationData::Unsimultaneous);
+    }
+
+    // Called once the board has slid the tile at from into to
+    fn tile_slid(&mut self, to: (usize, usize), from: (usize, usize), duration: f32) {
         if !self.peer {
             // Send to peer
-            self.transport.swap_tiles((i1, j1), (i2, j2), duration);
+            self.transport.swap_tiles(to, from, duration);
+        }
+        if let (Some(replay), Some(timer)) = (&mut self.replay, &self.timer) {
+            let offset = (from.0 as isize - to.0 as isize, from.1 as isize - to.1 as isize);
+            replay.record(timer.time_since_start(), Move::from_tile_offset(offset).expect("Tile slid from a cell that isn't adjacent"));
+        }
+        // Whatever the hint was, it's out of date now
+        self.hint = None;
+        self.animate_tile_to(to, duration);
+    }
+
+    // Slides tiles for the player, all at once if there are several.
+    // This counts as one multi-tile move.
+    fn play_moves(&mut self, moves: &[Move]) {
+        let dests = self.board.move_tiles(moves);
+        if dests.is_empty() {
+            return;
         }
+        self.total_multi_tile_moves += 1;
+        self.total_moves += dests.len() as u32;
+        self.tiles_slid_together(&dests);
+    }
+
+    // The cell whose tile is drawn under point
+    fn cell_at(&self, point: Vec2) -> Option<(usize, usize)> {
+        let side_len = self.tiles[0][0].borrow().side_len;
+        (0..self.board.rows()).flat_map(|i| (0..self.board.cols()).map(move |j| (i, j))).find(|&(i, j)| {
+            let pos = TilePosition::from_ij(i, j, side_len, self.x, self.y);
+            Rect { x: pos.x, y: pos.y, w: side_len as f32, h: side_len as f32 }.contains(point)
+        })
+    }
 
-        {
-            let old_tile = self.ref_board[i1][j1].clone();
-            if let None = old_tile {
-                self.blank_cell = (i2, j2);
+    // Sends the stats to the peer once the player has solved the board
+    fn check_player_completed(&mut self) {
+        if !self.peer {
+            // Immediately will happen during this
+            self.check_completed();
+            if let GameStage::FinishingAnimation = self.game_stage {
+                let stats = self.get_puzzle_statistics();
+                self.puzzle_statistics = Some(stats.clone());
+                self.transport.end_game(stats);
             }
+        }
+    }
 
-            self.ref_board[i1][j1] = self.ref_board[i2][j2].clone();
-            self.ref_board[i2][j2] = old_tile;
+    pub fn show_hint(&mut self, ctx: &mut Context) -> GameResult {
+        if self.hint.is_some() {
+            return Ok(());
         }
-        // Update coordinates and keyframes
+        let Some(mv) = Solver::new(Some(SOLVER_NODE_LIMIT)).next_move(&self.board) else { return Ok(()) };
+        let (i, j) = self.board.move_source(mv).expect("Solver returned an invalid move");
 
-        let tile_update = (*self.ref_board[i1][j1].as_ref().unwrap()).as_ref().borrow_mut();
+        // Every tile is the same size
+        let side_len = self.tiles[0][0].borrow().side_len;
+        self.hint = Some(HintOverlay::new(ctx, TilePosition::from_ij(i, j, side_len, self.x, self.y), (i, j), side_len, mv)?);
+        self.hints_used += 1;
+        Ok(())
+    }
 
-        let new_pos = TilePosition::from_ij(i1, j1, tile_update.side_len, self.x, self.y);
-        self.animation.push_seq(AnimationData::Generator((self.ref_board[i1][j1].as_ref().unwrap().clone(), new_pos, TILE_SLIDE_DURATION)));
+    // Queues up every move of a solution, then the finishing animation.
+    pub fn auto_solve(&mut self) {
+        let Some(solution) = Solver::new(Some(SOLVER_NODE_LIMIT)).solve(&self.board) else { return };
+        let speed = PLAYER.lock().unwrap().as_ref().map_or(DEFAULT_AUTO_SOLVE_SPEED, |player| player.player_settings.auto_solve_speed);
+        let duration = AUTO_SOLVE_SLIDE_DURATION / speed.max(1) as f32;
+
+        for mv in solution.moves {
+            let tile = self.board.move_source(mv).expect("Solver returned an invalid move");
+            self.swap_ref_tiles(self.board.blank_cell(), tile, duration);
+        }
+        self.auto_solved = true;
+        self.set_finishing_animation();
     }
 
-    pub fn check_completed(&mut self) {
-        for i in 0..self.ref_board.len() {
-            for j in 0..self.ref_board[i].len() {
-                if let Some(tile) = &self.ref_board[i][j] {
-                    if !Rc::ptr_eq(&self.tiles[i][j], tile) {
-                        return;
-                    }
+    // Slide every tile to where it is on the given board at once
+    pub fn set_board(&mut self, board: Board) -> GameResult {
+        if !self.peer {
+            self.transport.set_board(&board)?;
+        }
+        self.board = board;
+
+        self.animation.push_seq(AnimationData::Simultaneous);
+        for i in 0..self.board.rows() {
+            for j in 0..self.board.cols() {
+                if self.board.tile_at((i, j)).is_some() {
+                    self.animate_tile_to((i, j), TILE_SLIDE_DURATION * 2.0);
                 }
             }
         }
+        self.animation.push_seq(AnimationData::Unsimultaneous);
+        Ok(())
+    }
 
-        self.set_finishing_animation();
+    // Update coordinates and keyframes of the tile at (i, j)
+    fn animate_tile_to(&mut self, (i, j): (usize, usize), duration: f32) {
+        let (ti, tj) = self.board.home_of(self.board.tile_at((i, j)).expect("Cannot animate the blank cell"));
+        let tile = self.tiles[ti][tj].clone();
+
+        let new_pos = TilePosition::from_ij(i, j, tile.borrow().side_len, self.x, self.y);
+        self.animation.push_seq(AnimationData::Generator((tile, new_pos, duration)));
+    }
+
+    pub fn check_completed(&mut self) {
+        if self.board.is_solved() {
+            self.set_finishing_animation();
+        }
     }
 
     pub fn set_finishing_animation(&mut self) {
@@ -266,40 +421,69 @@ impl TileState {
                 let side_len = tile.side_len;
                 self.animation.push_seq(AnimationData::Sequence((
                     self.tiles[i][j].clone(),
-                    tile.to_state(
-                        TilePosition::from_ij_no_gap(i as usize, j as usize, side_len, self.x, self.y),
-                        TILE_SLIDE_DURATION * 4.0,
-                    ),
+                    tile.to_state(TilePosition::from_ij_no_gap(i, j, side_len, self.x, self.y), TILE_SLIDE_DURATION * 4.0),
                 )));
             }
         }
         self.game_stage = GameStage::FinishingAnimation;
     }
 
-    pub fn swap_random_tile_blank(&mut self) {
-        // This is so low effort
-        // Better method: start with gap at 0,0 and swap the gap with random adjacents over and over
-        let col_cnt_tiles = self.tiles.len();
-        let row_cnt_tiles = self.tiles[0].len();
-
-        // Choose a random adjacent tile
-        let tile2 = TileRandom::random_adjacent_tile(self.blank_cell, col_cnt_tiles, row_cnt_tiles);
-
-        // Redo the method if the tile2 was the previous swap
-        if Some(tile2) == self.previous_swap {
-            self.swap_random_tile_blank();
-            return;
-        } else {
-            self.previous_swap = Some(self.blank_cell);
-            self.swap_ref_tiles(self.blank_cell, tile2, TILE_SLIDE_DURATION)
-        }
-    }
-
     pub fn get_puzzle_statistics(&self) -> PuzzleStatistics {
         PuzzleStatistics {
             finish_time: Local::now(),
             duration: self.timer.as_ref().unwrap().time_since_start(),
             move_count: self.total_moves,
+            multi_tile_move_count: self.total_multi_tile_moves,
+            hints_used: self.hints_used,
+            replay_id: None,
+            grid: Some((self.board.rows(), self.board.cols())),
+            mode: if self.hints_used > 0 { GameMode::Hinted } else { GameMode::Singleplayer },
+            scramble_seed: self.scramble_seed,
+            opponent: None,
+        }
+    }
+
+    // Only the local player's games count, not replays or the game solving it
+    fn record_attempt(&self) {
+        if self.replay.is_none() || self.auto_solved {
+            return;
+        }
+        let mut opt_player = PLAYER.lock().unwrap();
+        let Some(player) = opt_player.as_mut() else { return };
+        if let Err(e) = player.record_attempt(&self.puzzle_id) {
+            error_toast::report_error("Couldn't save the game", &e);
+        }
+    }
+
+    // Saves the replay and adds the finished game to the player's statistics.
+    // Multiplayer games say who won and who against. A puzzle the game solved
+    // is only counted, it isn't one of the player's games.
+    pub fn record_completion(&self, multiplayer: Option<(bool, String)>) {
+        if self.auto_solved {
+            let mut opt_player = PLAYER.lock().unwrap();
+            let player = opt_player.as_mut().unwrap();
+            if let Err(e) = player.record_auto_solve(&self.puzzle_id) {
+                error_toast::report_error("Couldn't save the auto-solve", &e);
+            }
+            return;
+        }
+        let mut game_stat = self.puzzle_statistics.clone().unwrap_or_else(|| self.get_puzzle_statistics());
+        if let Some((won, opponent)) = multiplayer {
+            game_stat.mode = if won { GameMode::MultiplayerWin } else { GameMode::MultiplayerLoss };
+            game_stat.opponent = Some(opponent);
+        }
+        if let Some(replay) = &self.replay {
+            match replay.save() {
+                Ok(()) => game_stat.replay_id = Some(replay.id),
+                Err(e) => error_toast::report_error("Couldn't save the replay", &e),
+            }
+        }
+
+        let mut opt_player = PLAYER.lock().unwrap();
+        let player = opt_player.as_mut().unwrap();
+        // Still counted for now, the next game to finish saves it again
+        if let Err(e) = player.record(&self.puzzle_id, game_stat) {
+            error_toast::report_error("Couldn't save the game", &e);
         }
     }
 
@@ -310,98 +494,162 @@ impl TileState {
         ((w - (2.0 * TILE_PADDING_X + IMAGE_SIDELEN as f32)) / 2.0, (h - (2.0 * TILE_PADDING_Y + IMAGE_SIDELEN as f32)) / 2.0)
     }
 }
+// The board the peer sent, if it's a real board of this game's size with the
+// same tile missing. Anything else would put tiles where there are none.
+#[cfg(feature = "multiplayer")]
+fn peer_board(board: &Board, grid: (usize, usize), blank_tile: usize) -> Option<Board> {
+    Board::from_cells(board.rows(), board.cols(), board.cells().to_vec(), board.blank_tile())
+        .filter(|board| (board.rows(), board.cols()) == grid && board.blank_tile() == blank_tile)
+}
+
 impl Scene for TileState {
     #[cfg(feature = "multiplayer")]
     fn update(&mut self, _ctx: &mut Context) -> GameResult {
         use log::trace;
 
-        if self.peer {
-            if self.animation.finished() {
-                if let Some(msg) = self.transport.recv_message() {
-                    trace!("recv tile msg {:?}", msg);
-                    match msg {
-                        MultiplayerGameMessage::SwapTiles { i1j1, i2j2, duration } => {
-                            self.swap_ref_tiles(i1j1, i2j2, duration);
-                        }
-                        MultiplayerGameMessage::DeleteRandomTile((i, j)) => {
-                            // TODO move this to separate function to deal with animations
-                            self.delete_random_tile(Some((i, j)))?;
-                        }
-                        MultiplayerGameMessage::GameCompleted(stats) => {
-                            // TODO move this to separate function to deal with animations
-                            self.puzzle_statistics = Some(stats);
-                            println!("Peer completed game");
-                            self.set_finishing_animation();
-                        }
-                        MultiplayerGameMessage::ScramblingFinished => {
-                            self.game_stage = GameStage::Started;
+        if self.peer && self.animation.finished() {
+            if let Some(msg) = self.transport.recv_message() {
+                trace!("recv tile msg {:?}", msg);
+                match msg {
+                    MultiplayerGameMessage::SwapTiles { i1j1, i2j2, duration } => {
+                        self.swap_ref_tiles(i1j1, i2j2, duration);
+                    }
+                    MultiplayerGameMessage::DeleteRandomTile((i, j)) => {
+                        // TODO move this to separate function to deal with animations
+                        self.delete_random_tile(Some((i, j)))?;
+                    }
+                    MultiplayerGameMessage::SetBoard(board) => {
+                        let grid = (self.tiles.len(), self.tiles.first().map_or(0, Vec::len));
+                        match peer_board(&board, grid, self.board.blank_tile()) {
+                            Some(board) => self.set_board(board)?,
+                            None => log::warn!("Dropped a board from the peer that doesn't fit this game: {:?}", board),
                         }
-                        _ => {}
                     }
+                    MultiplayerGameMessage::GameCompleted(stats) => {
+                        // TODO move this to separate function to deal with animations
+                        self.puzzle_statistics = Some(stats);
+                        log::debug!("Peer completed game");
+                        self.set_finishing_animation();
+                    }
+                    MultiplayerGameMessage::ScramblingFinished => {
+                        self.game_stage = GameStage::Started;
+                    }
+                    _ => {}
                 }
             }
         }
         Ok(())
     }
 
-    fn handle_input_event(&mut self, _ctx: &mut Context, key_input: InputAction) {
-        let (i, j) = self.blank_cell;
-        let mut swap_tile = (i, j);
-
+    fn handle_input_event(&mut self, ctx: &mut Context, key_input: InputAction) {
         // TODO how do we make escape callable during animation?
         if let GameStage::Started = self.game_stage {
-            match key_input {
-                // Tile below space
-                InputAction::Up if i + 1 < self.ref_board.len() => swap_tile = (i + 1, j),
-                // Tile above space
-                InputAction::Down if i != 0 => swap_tile = (i - 1, j),
-                // Tile left of space
-                InputAction::Left if j + 1 < self.ref_board[i].len() => swap_tile = (i, j + 1),
-                // Tile right of space
-                InputAction::Right if j != 0 => swap_tile = (i, j - 1),
+            let moves = match key_input {
+                InputAction::Up => vec![Move::Up],
+                InputAction::Down => vec![Move::Down],
+                InputAction::Left => vec![Move::Left],
+                InputAction::Right => vec![Move::Right],
+                InputAction::LineUp => self.board.line_moves_to_edge(Move::Up),
+                InputAction::LineDown => self.board.line_moves_to_edge(Move::Down),
+                InputAction::LineLeft => self.board.line_moves_to_edge(Move::Left),
+                InputAction::LineRight => self.board.line_moves_to_edge(Move::Right),
                 // Cancel game
-                InputAction::Cancel => self.game_stage = GameStage::Cancelled,
-                _ => {}
-            }
-            if swap_tile != self.blank_cell {
-                self.swap_ref_tiles(self.blank_cell, swap_tile, TILE_SLIDE_DURATION);
-                self.total_moves += 1;
-            }
+                InputAction::Cancel => {
+                    self.game_stage = GameStage::Cancelled;
+                    vec![]
+                }
+                InputAction::Hint => {
+                    if let Err(e) = self.show_hint(ctx) {
+                        error_toast::report_error("Couldn't show a hint", &e);
+                    }
+                    vec![]
+                }
+                // Every tile counts, but a line slide is one multi-tile move
+                InputAction::Undo => {
+                    let slid = self.undo_move();
+                    if slid > 0 {
+                        let undo_counts_as_move =
+                            PLAYER.lock().unwrap().as_ref().is_none_or(|player| player.player_settings.undo_counts_as_move);
+                        if undo_counts_as_move {
+                            self.total_moves += slid;
+                            self.total_multi_tile_moves += 1;
+                        } else {
+                            self.total_moves = self.total_moves.saturating_sub(slid);
+                            self.total_multi_tile_moves = self.total_multi_tile_moves.saturating_sub(1);
+                        }
+                    }
+                    vec![]
+                }
+                InputAction::Redo => {
+                    let slid = self.redo_move();
+                    if slid > 0 {
+                        self.total_moves += slid;
+                        self.total_multi_tile_moves += 1;
+                    }
+                    vec![]
+                }
+                InputAction::CopyBoard => {
+                    let copied = clipboard::set_text(self.board.to_string());
+                    if let Err(e) = copied {
+                        error_toast::report(format!("Couldn't copy the board: {}", e));
+                    }
+                    vec![]
+                }
+                // No solving the puzzle for you in multiplayer
+                InputAction::AutoSolve if !self.peer && !self.transport.is_multiplayer() => {
+                    self.auto_solve();
+                    return;
+                }
+                _ => vec![],
+            };
+            self.play_moves(&moves);
             // TODO move this to the update method
-            if !self.peer {
-                // Immediately will happen during this
-                self.check_completed();
-                if let GameStage::FinishingAnimation = self.game_stage {
-                    let stats = self.get_puzzle_statistics();
-                    self.puzzle_statistics = Some(stats.clone());
-                    self.transport.end_game(stats);
+            self.check_player_completed();
+        }
+    }
+
+    fn handle_mouse_event(&mut self, _ctx: &mut Context, mouse_input: MouseAction) {
+        if self.peer || !matches!(self.game_stage, GameStage::Started) {
+            return;
+        }
+        let moves = match mouse_input {
+            // Clicking a tile in line with the blank slides it and everything in between
+            MouseAction::Click(point) => self.cell_at(point).map(|cell| self.board.line_moves(cell)).unwrap_or_default(),
+            MouseAction::Drag { from, to } => {
+                let delta = to - from;
+                let dragged = if delta.x.abs() > delta.y.abs() {
+                    if delta.x > 0.0 {
+                        Move::Right
+                    } else {
+                        Move::Left
+                    }
+                } else if delta.y > 0.0 {
+                    Move::Down
+                } else {
+                    Move::Up
+                };
+                let moves = self.cell_at(from).map(|cell| self.board.line_moves(cell)).unwrap_or_default();
+                // Only dragging toward the blank slides anything
+                if moves.first() == Some(&dragged) {
+                    moves
+                } else {
+                    vec![]
                 }
             }
-        }
+            MouseAction::Hover(_) => vec![],
+        };
+        self.play_moves(&moves);
+        self.check_player_completed();
     }
 
     fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
         match self.game_stage {
             GameStage::Finished | GameStage::Cancelled => {
                 if let GameStage::Finished = self.game_stage {
-                    // Update player completed puzzles,
-                    // point value?
-                    {
-                        let mut opt_player = PLAYER.lock().unwrap();
-                        let player = opt_player.as_mut().unwrap();
-
-                        let game_stat = self.get_puzzle_statistics();
-
-                        // TODO do we really want this? Should multiplayer stats get saved separately?
-                        if let Some(statistics) = player.completed_puzzles.get_mut(&self.img_num) {
-                            statistics.push(game_stat);
-                        } else {
-                            player.completed_puzzles.insert(self.img_num, vec![game_stat]);
-                        }
-                        player.save(ctx).expect("Failed to save player statistics");
-                    }
+                    self.record_completion(None);
                 }
-                Some(Box::new(PuzzleListing::new(ctx, 4 * ((self.img_num) / 4)).expect("Failed to return to puzzle listing")))
+                let puzzle_id = self.puzzle_id.clone();
+                Some(try_scene(ctx, "Couldn't Return to the Puzzles", move |ctx| PuzzleListing::showing(ctx, &puzzle_id), main_menu))
             }
             _ => None,
         }
@@ -423,20 +671,118 @@ impl Drawable for TileState {
         self.animation.advance(0.05);
         if self.animation.finished() {
             match self.game_stage {
-                GameStage::StartingAnimation => self.game_stage = GameStage::Started,
+                GameStage::StartingAnimation => {
+                    self.game_stage = GameStage::Started;
+                    self.record_attempt();
+                }
                 GameStage::FinishingAnimation => self.game_stage = GameStage::Finished,
                 _ => {}
             }
         }
 
-        for i in 0..self.ref_board.len() {
+        for tile_row in &self.tiles {
             // each tile in the row, so x
-            for j in 0..self.ref_board[i].len() {
-                let mut tile = self.tiles[i][j].as_ref().borrow_mut();
-                tile.draw(ctx, canvas)?;
+            for tile in tile_row {
+                tile.as_ref().borrow_mut().draw(ctx, canvas)?;
             }
         }
 
+        if let (GameStage::Started, Some(hint)) = (&self.game_stage, &mut self.hint) {
+            hint.draw(ctx, canvas)?;
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{
+        player::{save_file::tests::TempSaveDir, Player, PlayerSettings},
+        resources::image_loader::ImageFit,
+    };
+
+    use super::*;
+
+    fn play_as_new_player() {
+        let settings =
+            PlayerSettings { num_rows: 4, num_cols: 4, undo_counts_as_move: true, auto_solve_speed: 4, image_fit: ImageFit::Crop };
+        *PLAYER.lock().unwrap() = Some(Player::new("Ada".to_string(), settings));
+    }
+
+    #[test]
+    fn an_auto_solve_is_only_counted() {
+        let _dir = TempSaveDir::new();
+        play_as_new_player();
+        let puzzle_id = PuzzleId("mountains".to_string());
+        let tile_state = TileState { puzzle_id: puzzle_id.clone(), auto_solved: true, ..Default::default() };
+        tile_state.record_completion(None);
+        tile_state.record_completion(None);
+
+        let player = PLAYER.lock().unwrap().take().unwrap();
+        assert_eq!(player.auto_solves[&puzzle_id], 2);
+        assert!(player.completed_puzzles.is_empty());
+        assert!(player.attempts.is_empty());
+    }
+
+    #[test]
+    fn a_finished_game_is_one_of_the_players() {
+        let _dir = TempSaveDir::new();
+        play_as_new_player();
+        let puzzle_id = PuzzleId("mountains".to_string());
+        let stat = PuzzleStatistics {
+            finish_time: Local::now(),
+            duration: std::time::Duration::from_secs(30),
+            move_count: 20,
+            multi_tile_move_count: 18,
+            hints_used: 0,
+            replay_id: None,
+            grid: Some((4, 4)),
+            mode: GameMode::Singleplayer,
+            scramble_seed: None,
+            opponent: None,
+        };
+        let tile_state = TileState { puzzle_id: puzzle_id.clone(), puzzle_statistics: Some(stat), ..Default::default() };
+        tile_state.record_completion(None);
+
+        let player = PLAYER.lock().unwrap().take().unwrap();
+        assert!(player.auto_solves.is_empty());
+        assert_eq!(player.completed_puzzles[&puzzle_id].iter().map(|stat| stat.move_count).collect::<Vec<_>>(), [20]);
+    }
+
+    #[test]
+    fn an_auto_solve_isnt_a_started_game() {
+        let _dir = TempSaveDir::new();
+        play_as_new_player();
+        let puzzle_id = PuzzleId("mountains".to_string());
+        let replay = Some(Replay::new(puzzle_id.clone(), Board::new(4, 4, (3, 3))));
+        TileState { puzzle_id: puzzle_id.clone(), replay: replay.clone(), auto_solved: true, ..Default::default() }.record_attempt();
+        assert!(PLAYER.lock().unwrap().as_ref().unwrap().attempts.is_empty());
+
+        TileState { puzzle_id: puzzle_id.clone(), replay, ..Default::default() }.record_attempt();
+        let player = PLAYER.lock().unwrap().take().unwrap();
+        assert_eq!(player.attempts[&puzzle_id], 1);
+    }
+
+    #[cfg(feature = "multiplayer")]
+    #[test]
+    fn only_boards_that_fit_come_from_the_peer() {
+        // As it comes from the peer, with a blank cell that may not be right
+        let board = |rows: usize, cols: usize, cells: Vec<usize>, blank_tile: usize| -> Board {
+            serde_json::from_value(serde_json::json!({
+                "rows": rows, "cols": cols, "cells": cells, "blank_tile": blank_tile,
+                "blank_cell": [0, 0], "history": [], "redo_stack": [],
+            }))
+            .unwrap()
+        };
+        let sent = board(2, 2, vec![3, 1, 2, 0], 0);
+        let applied = peer_board(&sent, (2, 2), 0).unwrap();
+        assert_eq!((applied.cells(), applied.blank_cell()), (sent.cells(), (1, 1)));
+
+        // Another size, a different tile missing, a tile twice, too few cells
+        assert!(peer_board(&sent, (3, 3), 0).is_none());
+        assert!(peer_board(&sent, (2, 2), 1).is_none());
+        assert!(peer_board(&board(2, 2, vec![3, 1, 1, 0], 0), (2, 2), 0).is_none());
+        assert!(peer_board(&board(2, 2, vec![3, 1, 0], 0), (2, 2), 0).is_none());
+    }
+}
@@ -0,0 +1,153 @@
+// Decoded images, shared by every scene. Decoding happens on a worker thread so
+// big images don't hold up drawing, and the least recently used images are
+// dropped once the cache is over its budget.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+};
+
+use ggez::{GameError, GameResult};
+use image::DynamicImage;
+use lazy_static::lazy_static;
+
+use super::image_loader::PuzzleId;
+
+lazy_static! {
+    static ref CACHE: (Mutex<ImageCache>, Condvar) = (Mutex::new(ImageCache::default()), Condvar::new());
+    static ref WORKER: Mutex<Option<Sender<Job>>> = Mutex::new(None);
+}
+
+// A few full size images plus a listing's worth of thumbnails
+const CACHE_BYTES: usize = 256 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImageKind {
+    // Small and letterboxed, for listings
+    Thumbnail,
+    // For playing
+    Full,
+}
+
+pub type CacheKey = (PuzzleId, ImageKind);
+
+// Runs on the worker, so it can't use the ggez filesystem
+pub type LoadFn = Box<dyn FnOnce() -> GameResult<DynamicImage> + Send>;
+
+struct Job {
+    key: CacheKey,
+    load: LoadFn,
+}
+
+enum Entry {
+    Pending,
+    Ready(Arc<DynamicImage>),
+    // Kept so the same broken image isn't decoded over and over
+    Failed(String),
+}
+
+#[derive(Default)]
+pub struct ImageCache {
+    entries: HashMap<CacheKey, Entry>,
+    // Ready images, least recently used first
+    order: VecDeque<CacheKey>,
+    bytes: usize,
+}
+
+fn image_bytes(img: &DynamicImage) -> usize { img.as_bytes().len() }
+
+impl ImageCache {
+    fn touch(&mut self, key: &CacheKey) {
+        if let Some(idx) = self.order.iter().position(|k| k == key) {
+            let key = self.order.remove(idx).unwrap();
+            self.order.push_back(key);
+        }
+    }
+
+    fn insert(&mut self, key: CacheKey, result: GameResult<DynamicImage>) {
+        let entry = match result {
+            Ok(img) => {
+                self.bytes += image_bytes(&img);
+                self.order.push_back(key.clone());
+                Entry::Ready(Arc::new(img))
+            }
+            Err(e) => {
+                log::warn!("Couldn't load {}: {}", key.0, e);
+                Entry::Failed(e.to_string())
+            }
+        };
+        self.entries.insert(key, entry);
+
+        // The newest image always stays, even if it's bigger than the budget by itself
+        while self.bytes > CACHE_BYTES && self.order.len() > 1 {
+            let old_key = self.order.pop_front().unwrap();
+            if let Some(Entry::Ready(img)) = self.entries.remove(&old_key) {
+                self.bytes -= image_bytes(&img);
+            }
+        }
+    }
+
+    fn worker() -> Sender<Job> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        thread::Builder::new()
+            .name("image decoder".to_string())
+            .spawn(move || {
+                for job in receiver {
+                    let result = (job.load)();
+                    let (cache, loaded) = &*CACHE;
+                    cache.lock().unwrap().insert(job.key, result);
+                    loaded.notify_all();
+                }
+            })
+            .expect("Failed to start the image decoder");
+        sender
+    }
+
+    // Starts loading the image unless it's already loaded or on its way.
+    // load is only called if it's needed.
+    pub fn request(key: CacheKey, load: impl FnOnce() -> GameResult<LoadFn>) -> GameResult {
+        let mut cache = CACHE.0.lock().unwrap();
+        if cache.entries.contains_key(&key) {
+            return Ok(());
+        }
+        let job = Job { key: key.clone(), load: load()? };
+        cache.entries.insert(key, Entry::Pending);
+
+        let mut worker = WORKER.lock().unwrap();
+        worker.get_or_insert_with(Self::worker).send(job).map_err(|_| GameError::CustomError("The image decoder stopped".to_string()))
+    }
+
+    // None while the image is still loading or if it was never requested
+    pub fn get(key: &CacheKey) -> Option<GameResult<Arc<DynamicImage>>> {
+        let mut cache = CACHE.0.lock().unwrap();
+        let result = match cache.entries.get(key)? {
+            Entry::Pending => return None,
+            Entry::Ready(img) => Ok(img.clone()),
+            Entry::Failed(e) => Err(GameError::ResourceLoadError(e.clone())),
+        };
+        cache.touch(key);
+        Some(result)
+    }
+
+    // Blocks until a requested image has loaded
+    pub fn wait(key: &CacheKey) -> GameResult<Arc<DynamicImage>> {
+        let (cache, loaded) = &*CACHE;
+        let mut cache = cache.lock().unwrap();
+        loop {
+            match cache.entries.get(key) {
+                Some(Entry::Pending) => cache = loaded.wait(cache).unwrap(),
+                Some(Entry::Ready(img)) => {
+                    let img = img.clone();
+                    cache.touch(key);
+                    return Ok(img);
+                }
+                Some(Entry::Failed(e)) => return Err(GameError::ResourceLoadError(e.clone())),
+                None => return Err(GameError::CustomError(format!("{} was never requested", key.0))),
+            }
+        }
+    }
+}
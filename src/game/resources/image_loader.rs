@@ -1,53 +1,338 @@
 use std::{
     collections::HashMap,
+    fmt, fs,
+    io::{Cursor, Read},
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 
-use ggez::{graphics::Image, Context};
+use ggez::{
+    graphics::{Color, Image, ImageFormat},
+    Context, GameError, GameResult,
+};
+use image::{imageops::FilterType, io::Reader as ImageReader, DynamicImage, RgbaImage};
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{player::save_dir, ui::error_toast};
+
+use super::{
+    image_cache::{ImageCache, ImageKind, LoadFn},
+    library::{Library, LibraryImage},
+    pack::{pack_dirs, PackManifest, PuzzleInfo, BUILT_IN_PACK, MANIFEST_FILE},
+};
 
 lazy_static! {
     static ref IMAGES: Mutex<Option<ImageLoader>> = Mutex::new(None);
+    static ref FIT: Mutex<ImageFit> = Mutex::new(ImageFit::default());
+}
+
+// Listings and puzzle views show a square thumbnail this big
+pub const THUMBNAIL_SIDELEN: u32 = 512;
+
+// Identifies a puzzle by its image's file name without the extension, so
+// adding or removing other images doesn't change which puzzle is which.
+// Saved as a plain string, TOML can't have anything else as a key
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[serde(transparent)]
+pub struct PuzzleId(pub String);
+
+impl fmt::Display for PuzzleId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "{}", self.0) }
+}
+
+// What to do with an image that isn't the same shape as the board
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImageFit {
+    // Cut off whatever sticks out past the board
+    #[default]
+    Crop,
+    // Show the whole image with empty bars on the sides
+    Letterbox,
+}
+
+impl ImageFit {
+    // Scales the image to exactly width x height
+    pub fn apply(self, img: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+        match self {
+            ImageFit::Crop => img.resize_to_fill(width, height, FilterType::Lanczos3),
+            ImageFit::Letterbox => {
+                let resized = img.resize(width, height, FilterType::Lanczos3);
+                let mut boxed = RgbaImage::new(width, height);
+                image::imageops::overlay(
+                    &mut boxed,
+                    &resized.to_rgba8(),
+                    ((width - resized.width()) / 2) as i64,
+                    ((height - resized.height()) / 2) as i64,
+                );
+                DynamicImage::ImageRgba8(boxed)
+            }
+        }
+    }
+}
+
+enum ImageSource {
+    // In /images in the resources
+    Resources(PathBuf),
+    // Imported by the player
+    Library(LibraryImage),
+}
+
+impl ImageSource {
+    // Thumbnails are made once and kept on disk, so listings don't have to
+    // decode the full images
+    fn thumbnail_path(&self, id: &PuzzleId) -> PathBuf {
+        match self {
+            ImageSource::Resources(_) => save_dir().join("cache").join("thumbnails").join(format!("{}.png", id)),
+            ImageSource::Library(library_image) => library_image.thumbnail_path(),
+        }
+    }
+
+    // Resources have to be read here since the worker can't use the ggez filesystem
+    fn read(&self, ctx: &mut Context) -> GameResult<(Vec<u8>, String)> {
+        match self {
+            ImageSource::Resources(path) => {
+                let mut bytes = vec![];
+                ctx.fs.open(path)?.read_to_end(&mut bytes)?;
+                Ok((bytes, path.display().to_string()))
+            }
+            ImageSource::Library(library_image) => Ok((fs::read(library_image.path())?, library_image.file_name.clone())),
+        }
+    }
+}
+
+struct PuzzleImage {
+    info: PuzzleInfo,
+    source: ImageSource,
 }
 
 #[derive(Default)]
 pub struct ImageLoader {
-    images: HashMap<String, Arc<Image>>,
-    pub total: usize,
-    pub loaded: usize,
+    library: Library,
+    // In listing order
+    puzzles: Vec<PuzzleImage>,
+    indices: HashMap<PuzzleId, usize>,
+    // Shown until a thumbnail has loaded
+    placeholder: Option<Arc<Image>>,
+}
+
+// The format comes from the file's contents, not its extension
+fn decode(bytes: Vec<u8>, name: &str) -> GameResult<DynamicImage> {
+    ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()?
+        .decode()
+        .map_err(|e| GameError::ResourceLoadError(format!("Failed to decode {}: {}", name, e)))
+}
+
+// Numbered images come first in number order, then the rest by name
+fn listing_order(id: &PuzzleId) -> (Option<u64>, String) { (id.0.parse().ok(), id.0.clone()) }
+
+fn file_id(path: &Path) -> Option<PuzzleId> {
+    path.file_stem().and_then(|stem| stem.to_str()).filter(|stem| !stem.is_empty()).map(|stem| PuzzleId(stem.to_string()))
+}
+
+// Everything in a pack's directory, in listing order. The manifest's puzzles come
+// first in the order it gives, then any other images.
+fn pack_images(ctx: &mut Context, dir: &Path) -> GameResult<Vec<(PuzzleInfo, ImageSource)>> {
+    let manifest = PackManifest::load(ctx, dir).unwrap_or_else(|e| {
+        log::warn!("Ignoring the manifest in {}: {}", dir.display(), e);
+        None
+    });
+    let listed = manifest.map_or(vec![], |manifest| manifest.puzzles);
+
+    let mut others: Vec<(PuzzleId, PathBuf)> = ctx
+        .fs
+        .read_dir(dir)?
+        .filter(|path| ctx.fs.is_file(path) && path.file_name().is_some_and(|name| name != MANIFEST_FILE))
+        .filter(|path| image::ImageFormat::from_path(path).is_ok())
+        .filter(|path| !listed.iter().any(|puzzle| dir.join(&puzzle.file) == *path))
+        .filter_map(|path| Some((file_id(&path)?, path)))
+        .collect();
+    others.sort_by_key(|(id, _)| listing_order(id));
+
+    Ok(listed
+        .into_iter()
+        .map(|puzzle| {
+            let path = dir.join(&puzzle.file);
+            (puzzle, ImageSource::Resources(path))
+        })
+        .chain(others.into_iter().map(|(id, path)| (PuzzleInfo::untitled(id), ImageSource::Resources(path))))
+        .collect())
 }
 
 impl ImageLoader {
+    // Only lists the puzzles, images are loaded when they're needed
     pub fn get_load_status(ctx: &mut Context) -> (usize, usize) {
         let mut images_b = IMAGES.lock().unwrap();
-        if let None = *images_b {
-            let mut loader = ImageLoader::default();
-            loader.total = ctx.fs.read_dir("/images").expect("Failed to read dir").count();
+        if images_b.is_none() {
+            let library = Library::load().unwrap_or_else(|e| {
+                log::warn!("Starting with an empty library: {}", e);
+                Library::default()
+            });
+
+            // A pack that can't be read is left out, the rest can still be played
+            let dirs = pack_dirs(ctx).unwrap_or_else(|e| {
+                error_toast::report_error("Couldn't list the puzzle packs", &e);
+                vec![PathBuf::from(BUILT_IN_PACK)]
+            });
+            let mut found: Vec<(PuzzleInfo, ImageSource)> = vec![];
+            for dir in dirs {
+                match pack_images(ctx, &dir) {
+                    Ok(images) => found.extend(images),
+                    Err(e) => error_toast::report_error(&format!("Couldn't read the puzzles in {}", dir.display()), &e),
+                }
+            }
+            // Imported images go after the packs in the order they were imported
+            found.extend(
+                library
+                    .images
+                    .iter()
+                    .map(|library_image| (PuzzleInfo::untitled(library_image.id.clone()), ImageSource::Library(library_image.clone()))),
+            );
 
-            *images_b = Some(loader);
+            let placeholder = Image::from_solid(ctx, THUMBNAIL_SIDELEN, Color::new(0.0, 0.0, 0.0, 0.0));
+            let mut images = ImageLoader { library, placeholder: Some(Arc::new(placeholder)), ..Default::default() };
+            for (info, source) in found {
+                if let Err(e) = images.add(info, source) {
+                    log::warn!("Skipping an image: {}", e);
+                }
+            }
+            *images_b = Some(images);
         }
 
+        let count = images_b.as_ref().unwrap().puzzles.len();
+        (count, count)
+    }
+
+    fn add(&mut self, info: PuzzleInfo, source: ImageSource) -> GameResult {
+        // Ids have to be unique across all the packs
+        if self.indices.contains_key(&info.id) {
+            return Err(GameError::ResourceLoadError(format!("There is already a puzzle called {}", info.id)));
+        }
+        self.indices.insert(info.id.clone(), self.puzzles.len());
+        self.puzzles.push(PuzzleImage { info, source });
+        Ok(())
+    }
+
+    // Copies the image into the player's library and lists it after the others
+    pub fn import(path: &Path) -> GameResult<PuzzleId> {
+        let mut images_b = IMAGES.lock().unwrap();
         let images = images_b.as_mut().unwrap();
-        if images.loaded < images.total {
-            // Load next image.
-            let img_texture = Image::from_path(ctx, format!("/images/{}.jpg", images.loaded)).expect("Failed to open image");
-            images.images.insert(format!("{}", images.loaded), Arc::new(img_texture));
-            images.loaded += 1;
+        let indices = &images.indices;
+        let library_image = images.library.import(path, |id| indices.contains_key(id))?;
+        let id = library_image.id.clone();
+        images.add(PuzzleInfo::untitled(id.clone()), ImageSource::Library(library_image))?;
+        Ok(id)
+    }
+
+    fn request(ctx: &mut Context, id: &PuzzleId, kind: ImageKind) -> GameResult {
+        let images_b = IMAGES.lock().unwrap();
+        let images = images_b.as_ref().unwrap();
+        let idx = *images.indices.get(id).ok_or_else(|| GameError::CustomError(format!("There is no puzzle {}", id)))?;
+        let source = &images.puzzles[idx].source;
+
+        ImageCache::request((id.clone(), kind), || -> GameResult<LoadFn> {
+            let thumbnail_path = source.thumbnail_path(id);
+            Ok(match kind {
+                ImageKind::Thumbnail if thumbnail_path.exists() => Box::new(move || {
+                    image::open(&thumbnail_path).map_err(|e| GameError::ResourceLoadError(format!("Failed to read thumbnail: {}", e)))
+                }),
+                // The whole image is shown in thumbnails, whatever the board's shape
+                ImageKind::Thumbnail => {
+                    let (bytes, name) = source.read(ctx)?;
+                    Box::new(move || {
+                        let thumbnail = ImageFit::Letterbox.apply(&decode(bytes, &name)?, THUMBNAIL_SIDELEN, THUMBNAIL_SIDELEN);
+                        // Not being able to cache it isn't worth failing over
+                        if let Err(e) = fs::create_dir_all(thumbnail_path.parent().unwrap())
+                            .map_err(|e| e.to_string())
+                            .and_then(|_| thumbnail.save_with_format(&thumbnail_path, image::ImageFormat::Png).map_err(|e| e.to_string()))
+                        {
+                            log::warn!("Couldn't save the thumbnail for {}: {}", name, e);
+                        }
+                        Ok(thumbnail)
+                    })
+                }
+                ImageKind::Full => {
+                    let (bytes, name) = source.read(ctx)?;
+                    Box::new(move || decode(bytes, &name))
+                }
+            })
+        })
+    }
+
+    // None until the thumbnail has loaded in the background. An error means
+    // it won't load, so there's no use asking again.
+    pub fn thumbnail(ctx: &mut Context, id: &PuzzleId) -> GameResult<Option<Arc<Image>>> {
+        Self::request(ctx, id, ImageKind::Thumbnail)?;
+        let Some(thumbnail) = ImageCache::get(&(id.clone(), ImageKind::Thumbnail)) else { return Ok(None) };
+        let thumbnail = thumbnail?.to_rgba8();
+        Ok(Some(Arc::new(Image::from_pixels(ctx, thumbnail.as_raw(), ImageFormat::Rgba8UnormSrgb, thumbnail.width(), thumbnail.height()))))
+    }
+
+    // The same size as the thumbnails
+    pub fn placeholder() -> Arc<Image> { IMAGES.lock().unwrap().as_ref().unwrap().placeholder.clone().unwrap() }
+
+    // Starts decoding the full image so it's ready by the time a game starts
+    pub fn prefetch(ctx: &mut Context, id: &PuzzleId) {
+        if let Err(e) = Self::request(ctx, id, ImageKind::Full) {
+            log::warn!("Couldn't load {}: {}", id, e);
         }
-        (images.loaded, images.total)
     }
 
-    pub fn get_img(num: usize) -> Option<Arc<Image>> {
+    // The full size image for a game, waits for it if it's still being decoded
+    pub fn full_image(ctx: &mut Context, id: &PuzzleId) -> GameResult<Arc<DynamicImage>> {
+        Self::request(ctx, id, ImageKind::Full)?;
+        ImageCache::wait(&(id.clone(), ImageKind::Full))
+    }
+
+    pub fn has_img(id: &PuzzleId) -> bool {
         let images_b = IMAGES.lock().unwrap();
         let images = images_b.as_ref().unwrap();
-        images.images.get(&format!("{}", num)).cloned()
+        images.indices.contains_key(id)
+    }
+
+    // Where the puzzle is in the listing
+    pub fn index_of(id: &PuzzleId) -> Option<usize> { IMAGES.lock().unwrap().as_ref().unwrap().indices.get(id).copied() }
+
+    pub fn id_at(idx: usize) -> Option<PuzzleId> {
+        IMAGES.lock().unwrap().as_ref().unwrap().puzzles.get(idx).map(|puzzle| puzzle.info.id.clone())
     }
 
-    // This exists for efficiency purposes
-    // to avoid the Arc clone on get_img
-    pub fn has_img(num: usize) -> bool {
+    pub fn ids() -> Vec<PuzzleId> { IMAGES.lock().unwrap().as_ref().unwrap().puzzles.iter().map(|puzzle| puzzle.info.id.clone()).collect() }
+
+    // From the pack's manifest
+    pub fn info(id: &PuzzleId) -> Option<PuzzleInfo> {
         let images_b = IMAGES.lock().unwrap();
         let images = images_b.as_ref().unwrap();
-        images.images.contains_key(&format!("{}", num))
+        images.indices.get(id).map(|&idx| images.puzzles[idx].info.clone())
+    }
+
+    // Titles of the packs with a manifest, in listing order
+    pub fn packs() -> Vec<String> {
+        let mut packs: Vec<String> = vec![];
+        for puzzle in IMAGES.lock().unwrap().as_ref().unwrap().puzzles.iter() {
+            if let Some(pack) = &puzzle.info.pack {
+                if !packs.contains(pack) {
+                    packs.push(pack.clone());
+                }
+            }
+        }
+        packs
+    }
+
+    pub fn title(id: &PuzzleId) -> String {
+        if let Some(title) = Self::info(id).and_then(|info| info.title) {
+            return title;
+        }
+        // Keeps the old numbering for the images that come with the game
+        match id.0.parse::<usize>() {
+            Ok(num) => format!("Puzzle {}", num + 1),
+            Err(_) => id.0.clone(),
+        }
     }
+
+    // From the player's settings
+    pub fn fit() -> ImageFit { *FIT.lock().unwrap() }
+
+    pub fn set_fit(fit: ImageFit) { *FIT.lock().unwrap() = fit; }
 }
@@ -0,0 +1,107 @@
+// Images the player has imported. They're copied into the save directory, so
+// the puzzles stay around even if the original file is moved or deleted.
+
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use ggez::{GameError, GameResult};
+use image::ImageFormat;
+use serde::{Deserialize, Serialize};
+
+use crate::game::player::save_dir;
+
+use super::image_loader::{ImageFit, PuzzleId, THUMBNAIL_SIDELEN};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LibraryImage {
+    pub id: PuzzleId,
+    // In library/images
+    pub file_name: String,
+    // Where it was imported from
+    pub source: PathBuf,
+    pub imported: DateTime<Local>,
+}
+
+impl LibraryImage {
+    pub fn path(&self) -> PathBuf { Library::dir().join("images").join(&self.file_name) }
+
+    pub fn thumbnail_path(&self) -> PathBuf { Library::dir().join("thumbnails").join(format!("{}.png", self.file_name)) }
+
+    // Letterboxed, like the previews in the listing
+    pub fn write_thumbnail(&self) -> GameResult<image::DynamicImage> {
+        let img =
+            image::open(self.path()).map_err(|e| GameError::ResourceLoadError(format!("Failed to read {}: {}", self.file_name, e)))?;
+        let thumbnail = ImageFit::Letterbox.apply(&img, THUMBNAIL_SIDELEN, THUMBNAIL_SIDELEN);
+        fs::create_dir_all(Library::dir().join("thumbnails"))?;
+        thumbnail
+            .save_with_format(self.thumbnail_path(), ImageFormat::Png)
+            .map_err(|e| GameError::FilesystemError(format!("Failed to save thumbnail for {}: {}", self.file_name, e)))?;
+        Ok(thumbnail)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct Library {
+    // In the order they were imported
+    pub images: Vec<LibraryImage>,
+}
+
+impl Library {
+    fn dir() -> PathBuf { save_dir().join("library") }
+
+    // An empty library if nothing has been imported yet
+    pub fn load() -> GameResult<Self> {
+        match File::open(Self::dir().join("library.dat")) {
+            Ok(library_file) =>
+                bincode::deserialize_from(library_file).map_err(|_| GameError::FilesystemError("Failed to read library.dat".to_string())),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self) -> GameResult {
+        fs::create_dir_all(Self::dir())?;
+        let library_file = File::create(Self::dir().join("library.dat"))?;
+        bincode::serialize_into(library_file, self).map_err(|_| GameError::FilesystemError("Failed to save library.dat".to_string()))
+    }
+
+    // Copies the image into the library and saves it. is_taken says whether an id
+    // is already used by another puzzle.
+    pub fn import(&mut self, source: &Path, is_taken: impl Fn(&PuzzleId) -> bool) -> GameResult<LibraryImage> {
+        let bytes = fs::read(source).map_err(|e| GameError::FilesystemError(format!("Couldn't read {}: {}", source.display(), e)))?;
+        let format = image::guess_format(&bytes).map_err(|_| GameError::ResourceLoadError("That file isn't an image".to_string()))?;
+        if !matches!(format, ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::WebP | ImageFormat::Gif | ImageFormat::Bmp) {
+            return Err(GameError::ResourceLoadError("Only PNG, JPEG, WebP, GIF and BMP images can be imported".to_string()));
+        }
+        // Make sure it can be played before it's added
+        image::load_from_memory_with_format(&bytes, format)
+            .map_err(|e| GameError::ResourceLoadError(format!("The image couldn't be read: {}", e)))?;
+
+        // Named after the file, with a number on the end if that's taken
+        let stem: String = source
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("image")
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        let is_used = |id: &PuzzleId| is_taken(id) || self.images.iter().any(|image| &image.id == id);
+        let id = (1..).map(|n| PuzzleId(if n == 1 { stem.clone() } else { format!("{}-{}", stem, n) })).find(|id| !is_used(id)).unwrap();
+
+        let library_image = LibraryImage {
+            file_name: format!("{}.{}", id, format.extensions_str()[0]),
+            id,
+            source: source.to_path_buf(),
+            imported: Local::now(),
+        };
+        fs::create_dir_all(Self::dir().join("images"))?;
+        fs::write(library_image.path(), &bytes)?;
+        library_image.write_thumbnail()?;
+
+        self.images.push(library_image.clone());
+        self.save()?;
+        Ok(library_image)
+    }
+}
@@ -1,39 +1,93 @@
+use std::path::PathBuf;
+
 use crate::game::{
     drawable::Drawable,
-    gmenu::{game_menu::GameMenu, main_menu::MainMenu},
-    player::{settings_scene::SettingsScene, Player},
+    player::{
+        profiles_scene::ProfilesScene, save_error_scene::SaveErrorScene, settings_scene::SettingsScene, BadSave, Player, Startup, PLAYER,
+    },
+    puzzle::tiles::{Board, Scramble, TileState},
     scene::Scene,
+    ui::error_scene::{back_to_main_menu, main_menu, show_error, try_scene},
 };
 use ggez::{
     graphics::{Canvas, DrawParam, Text, TextFragment},
     Context, GameResult,
 };
 
-use super::{image_loader::ImageLoader, theme::Theme};
+use super::{
+    image_loader::{ImageLoader, PuzzleId},
+    theme::Theme,
+};
+
+// A game to go straight into once everything is loaded
+pub enum StartGame {
+    // Puzzle (the first one if not given) and board
+    Custom(Option<PuzzleId>, Board),
+    // Puzzle and grid size, otherwise the player's setting is used
+    Puzzle(PuzzleId, Option<(usize, usize)>),
+}
+
+// Set from the command line
+#[derive(Default)]
+pub struct StartOptions {
+    pub theme_file: Option<PathBuf>,
+    pub start_game: Option<StartGame>,
+}
 
 #[derive(Default)]
 pub struct ResourceManager {
-    intro: bool,
+    startup: Startup,
+    bad_saves: Vec<BadSave>,
     player_loaded: bool,
     theme_loaded: bool,
     images_loaded: bool,
+    start_game: Option<StartGame>,
 }
 impl ResourceManager {
-    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+    pub fn new(ctx: &mut Context, options: StartOptions) -> GameResult<Self> {
         // So the main UI doesn't panic
-        Theme::load(ctx)?;
-        Ok(Self { theme_loaded: true, ..Default::default() })
+        Theme::load(ctx, options.theme_file.as_deref())?;
+        Ok(Self { theme_loaded: true, start_game: options.start_game, ..Default::default() })
+    }
+
+    fn create_start_game(ctx: &mut Context, start_game: StartGame) -> GameResult<TileState> {
+        let pos = TileState::center_xy(ctx);
+        match start_game {
+            StartGame::Custom(puzzle_id, board) => {
+                let puzzle_id = puzzle_id.or_else(|| ImageLoader::id_at(0)).unwrap_or_default();
+                TileState::new_custom(ctx, &puzzle_id, &board, pos)
+            }
+            StartGame::Puzzle(puzzle_id, num_rows_cols) => {
+                let num_rows_cols =
+                    num_rows_cols.unwrap_or_else(|| PLAYER.lock().unwrap().as_ref().unwrap().player_settings.num_rows_cols());
+                TileState::new_singleplayer(ctx, &puzzle_id, num_rows_cols, Scramble::default(), pos)
+            }
+        }
     }
 }
 
 impl Scene for ResourceManager {
     fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
         if self.player_loaded && self.theme_loaded && self.images_loaded {
-            // TODO better error handling here.
-            return Some(if self.intro {
-                Box::new(SettingsScene::new(ctx, true).ok()?)
+            return Some(if !self.bad_saves.is_empty() {
+                // Sorted out before anything else, a game from the command line included
+                let bad_saves = std::mem::take(&mut self.bad_saves);
+                match SaveErrorScene::new(ctx, bad_saves) {
+                    Ok(scene) => Box::new(scene),
+                    Err(e) => show_error(ctx, "Couldn't Show the Saves That Couldn't Be Read", &e, main_menu),
+                }
+            } else if self.startup == Startup::Intro {
+                // New players have to set up their profile before playing anything
+                try_scene(ctx, "Couldn't Make a New Profile", |ctx| SettingsScene::new(ctx, true), main_menu)
+            } else if let Some(start_game) = self.start_game.take() {
+                match Self::create_start_game(ctx, start_game) {
+                    Ok(tile_state) => Box::new(tile_state),
+                    Err(e) => show_error(ctx, "Couldn't Start the Game", &e, main_menu),
+                }
+            } else if self.startup == Startup::PickProfile {
+                try_scene(ctx, "Couldn't List the Profiles", ProfilesScene::new, main_menu)
             } else {
-                Box::new(GameMenu::new::<MainMenu>(ctx).ok()?)
+                back_to_main_menu(ctx)
             });
         }
         None
@@ -45,14 +99,20 @@ impl Drawable for ResourceManager {
     fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
         let mut status_text = "Sliding Puzzle Resource Loader\n\n".to_string();
 
-        if !self.player_loaded { self.intro = Player::startup(ctx); self.player_loaded = true; }
+        if !self.player_loaded {
+            // Before the profiles load, so a bad save isn't mistaken for no save
+            self.bad_saves = Player::quarantine_bad_saves();
+            self.startup = Player::startup();
+            self.player_loaded = true;
+        }
 
         if self.player_loaded { status_text += "Player loaded successfully.\n" }
         if self.theme_loaded { status_text += "Theme loaded successfully.\n" }
         if !self.images_loaded {
+            // Images themselves load in the background once they're needed
             let (loaded, total) = ImageLoader::get_load_status(ctx);
-            status_text += &format!("{}/{} images loaded.\n", loaded, total);
-            if loaded / total == 1 {
+            status_text += &format!("{} puzzles found.\n", total);
+            if loaded == total {
                 self.images_loaded = true;
             }
         }
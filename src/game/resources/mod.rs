@@ -1,5 +1,8 @@
+pub mod image_cache;
 pub mod image_loader;
+pub mod library;
 pub mod manager;
+pub mod pack;
 pub mod theme;
 
-pub use manager::ResourceManager;
+pub use manager::{ResourceManager, StartGame, StartOptions};
@@ -0,0 +1,116 @@
+// Puzzle packs are directories of images in the resources with a pack.json
+// describing them. /images is the pack that comes with the game, others go in
+// /packs/<name>/. Images in a pack that aren't in its pack.json are still
+// playable, they just don't have a title or credits.
+//
+// {
+//     "id": "animals",
+//     "title": "Animals",
+//     "author": "Someone",
+//     "license": "CC BY 4.0",
+//     "puzzles": [
+//         { "id": "cat", "file": "cat.jpg", "title": "Sleepy Cat", "grid": [4, 4], "tags": ["cats"] }
+//     ]
+// }
+
+use std::path::{Path, PathBuf};
+
+use ggez::{Context, GameError, GameResult};
+use serde::{Deserialize, Serialize};
+
+use super::image_loader::PuzzleId;
+
+pub const MANIFEST_FILE: &str = "pack.json";
+
+// Everything but id and file can be left out
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PuzzleInfo {
+    pub id: PuzzleId,
+    // Relative to the pack's directory
+    #[serde(default)]
+    pub file: String,
+    pub title: Option<String>,
+    // Taken from the pack if not given
+    pub author: Option<String>,
+    // e.g. "Photo by Someone on Unsplash"
+    pub attribution: Option<String>,
+    pub license: Option<String>,
+    // Rows and columns
+    pub grid: Option<(usize, usize)>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // The title of the pack it's from
+    #[serde(skip)]
+    pub pack: Option<String>,
+}
+
+impl PuzzleInfo {
+    // For images without an entry in a manifest
+    pub fn untitled(id: PuzzleId) -> Self { Self { id, ..Default::default() } }
+
+    // Author, license etc. as lines to show under the puzzle
+    pub fn credits(&self) -> Vec<String> {
+        let mut lines = vec![];
+        if let Some(pack) = &self.pack {
+            lines.push(format!("From {}", pack));
+        }
+        if let Some(author) = &self.author {
+            lines.push(format!("By {}", author));
+        }
+        if let Some(attribution) = &self.attribution {
+            lines.push(attribution.clone());
+        }
+        if let Some(license) = &self.license {
+            lines.push(format!("License: {}", license));
+        }
+        if let Some((rows, cols)) = self.grid {
+            lines.push(format!("Best played at {}x{}", rows, cols));
+        }
+        if !self.tags.is_empty() {
+            lines.push(format!("Tags: {}", self.tags.join(", ")));
+        }
+        lines
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PackManifest {
+    pub id: String,
+    pub title: String,
+    pub author: Option<String>,
+    pub license: Option<String>,
+    // In listing order
+    pub puzzles: Vec<PuzzleInfo>,
+}
+
+impl PackManifest {
+    // None if the directory has no manifest
+    pub fn load(ctx: &mut Context, dir: &Path) -> GameResult<Option<Self>> {
+        let path = dir.join(MANIFEST_FILE);
+        if !ctx.fs.is_file(&path) {
+            return Ok(None);
+        }
+        let mut manifest: Self = serde_json::from_reader(ctx.fs.open(&path)?)
+            .map_err(|e| GameError::ResourceLoadError(format!("Failed to read {}: {}", path.display(), e)))?;
+
+        for puzzle in manifest.puzzles.iter_mut() {
+            puzzle.pack = Some(manifest.title.clone());
+            puzzle.author = puzzle.author.take().or_else(|| manifest.author.clone());
+            puzzle.license = puzzle.license.take().or_else(|| manifest.license.clone());
+        }
+        Ok(Some(manifest))
+    }
+}
+
+pub const BUILT_IN_PACK: &str = "/images";
+
+// /images, then /packs/* by name
+pub fn pack_dirs(ctx: &mut Context) -> GameResult<Vec<PathBuf>> {
+    let mut dirs = vec![PathBuf::from(BUILT_IN_PACK)];
+    if ctx.fs.is_dir("/packs") {
+        let mut packs: Vec<PathBuf> = ctx.fs.read_dir("/packs")?.filter(|path| ctx.fs.is_dir(path)).collect();
+        packs.sort();
+        dirs.extend(packs);
+    }
+    Ok(dirs)
+}
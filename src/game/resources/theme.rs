@@ -1,4 +1,4 @@
-use std::sync::Mutex;
+use std::{fs::File, path::Path, sync::Mutex};
 
 use ggez::{
     graphics::{Color, FontData},
@@ -26,12 +26,14 @@ pub struct Theme {
 
 impl Theme {
     // NOTE that this will overwrite a theme that has already been loaded.
-    pub fn load(ctx: &mut Context) -> GameResult {
+    // theme_file is a theme outside of the resources, e.g. from the command line.
+    pub fn load(ctx: &mut Context, theme_file: Option<&Path>) -> GameResult {
         let mut theme = THEME.lock().unwrap();
-        *theme = Some(
-            serde_json::from_reader(ctx.fs.open("/theme.json")?)
-                .map_err(|e| GameError::FilesystemError(format!("Failed to read theme: {}", e)))?,
-        );
+        let parsed = match theme_file {
+            Some(path) => serde_json::from_reader(File::open(path)?),
+            None => serde_json::from_reader(ctx.fs.open("/theme.json")?),
+        };
+        *theme = Some(parsed.map_err(|e| GameError::FilesystemError(format!("Failed to read theme: {}", e)))?);
 
         // Load font
         let theme = theme.as_ref().unwrap();
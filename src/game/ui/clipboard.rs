@@ -0,0 +1,19 @@
+// Copying and pasting text. Without the clipboard feature there's no
+// clipboard, and both say so instead.
+
+#[cfg(feature = "clipboard")]
+use arboard::Clipboard;
+
+#[cfg(feature = "clipboard")]
+pub fn get_text() -> Result<String, String> { Clipboard::new().and_then(|mut clipboard| clipboard.get_text()).map_err(|e| e.to_string()) }
+
+#[cfg(feature = "clipboard")]
+pub fn set_text(text: String) -> Result<(), String> {
+    Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text)).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn get_text() -> Result<String, String> { Err("this build doesn't have clipboard support".to_string()) }
+
+#[cfg(not(feature = "clipboard"))]
+pub fn set_text(_text: String) -> Result<(), String> { Err("this build doesn't have clipboard support".to_string()) }
@@ -0,0 +1,148 @@
+// Shown instead of a scene that couldn't be made, so the player can try again
+// or go back instead of the game closing.
+//
+// Up/Down, Enter -> try again, go back or quit
+// Escape -> go back
+
+use std::process::exit;
+
+use ggez::{Context, GameError, GameResult};
+
+use crate::game::{
+    animation::DrawablePos,
+    drawable::Drawable,
+    gmenu::{
+        game_menu::GameMenu,
+        main_menu::MainMenu,
+        menu_item_list::{GameMenuItemList, NewGameMenuItemData, NewGameMenuItemDataVariant},
+    },
+    input::{mouse::MouseAction, InputAction},
+    resources::theme::Theme,
+    scene::Scene,
+};
+
+use super::{error_message, uitext::UIText};
+
+pub type MakeScene = Box<dyn Fn(&mut Context) -> GameResult<Box<dyn Scene>>>;
+
+enum Next {
+    Retry,
+    Back,
+}
+
+pub struct ErrorScene {
+    title_text: UIText,
+    message_text: UIText,
+    options: GameMenuItemList,
+    // Both are kept, going back can fail too
+    retry: Option<MakeScene>,
+    back: MakeScene,
+
+    next: Option<Next>,
+}
+
+impl ErrorScene {
+    pub fn new(ctx: &mut Context, title: &str, e: &GameError, retry: Option<MakeScene>, back: MakeScene) -> GameResult<Self> {
+        log::error!("{}: {}", title, e);
+        let title_text = UIText::new(title.to_string(), Theme::error_color(), 78.0, DrawablePos { x: 90.0, y: 90.0 });
+        let t_sz = title_text.text.measure(ctx)?;
+        let message_text = UIText::new(error_message(e), Theme::fg_color(), 38.0, DrawablePos { x: 90.0, y: 110.0 + t_sz.y });
+        let m_sz = message_text.text.measure(ctx)?;
+
+        let mut items = vec![];
+        if retry.is_some() {
+            items.push(NewGameMenuItemData {
+                variant: NewGameMenuItemDataVariant::TextItem { text: "Try Again".to_string() },
+                next_page: None,
+            });
+        }
+        items.push(NewGameMenuItemData { variant: NewGameMenuItemDataVariant::TextItem { text: "Back".to_string() }, next_page: None });
+        items.push(NewGameMenuItemData { variant: NewGameMenuItemDataVariant::TextItem { text: "Quit".to_string() }, next_page: None });
+        let options = GameMenuItemList::new(ctx, items, 90.0, 170.0 + t_sz.y + m_sz.y, 600.0, 80.0)?;
+
+        Ok(Self { title_text, message_text, options, retry, back, next: None })
+    }
+
+    fn activate_selected(&mut self) {
+        let selected = self.options.selected() + if self.retry.is_some() { 0 } else { 1 };
+        match selected {
+            0 => self.next = Some(Next::Retry),
+            1 => self.next = Some(Next::Back),
+            _ => exit(0),
+        }
+    }
+}
+
+// The scene make gives, or an error scene that can try making it again. Back
+// goes to the scene back gives.
+pub fn try_scene<S: Scene + 'static>(
+    ctx: &mut Context, title: &str, make: impl Fn(&mut Context) -> GameResult<S> + 'static,
+    back: impl Fn(&mut Context) -> GameResult<Box<dyn Scene>> + 'static,
+) -> Box<dyn Scene> {
+    match make(ctx) {
+        Ok(scene) => Box::new(scene),
+        Err(e) => {
+            let retry: MakeScene = Box::new(move |ctx| make(ctx).map(|scene| Box::new(scene) as Box<dyn Scene>));
+            Box::new(ErrorScene::new(ctx, title, &e, Some(retry), Box::new(back)).expect("Failed to create error scene"))
+        }
+    }
+}
+
+// For what can't be tried again, like a game whose connection has been used up
+pub fn show_error(
+    ctx: &mut Context, title: &str, e: &GameError, back: impl Fn(&mut Context) -> GameResult<Box<dyn Scene>> + 'static,
+) -> Box<dyn Scene> {
+    Box::new(ErrorScene::new(ctx, title, e, None, Box::new(back)).expect("Failed to create error scene"))
+}
+
+// Where most scenes go back to when they can't be made
+pub fn main_menu(ctx: &mut Context) -> GameResult<Box<dyn Scene>> { Ok(Box::new(GameMenu::new::<MainMenu>(ctx)?)) }
+
+pub fn back_to_main_menu(ctx: &mut Context) -> Box<dyn Scene> {
+    try_scene(ctx, "Couldn't Return to the Main Menu", GameMenu::new::<MainMenu>, main_menu)
+}
+
+impl Drawable for ErrorScene {
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut ggez::graphics::Canvas) -> GameResult {
+        self.title_text.draw(ctx, canvas)?;
+        self.message_text.draw(ctx, canvas)?;
+        self.options.draw(ctx, canvas)
+    }
+}
+
+impl Scene for ErrorScene {
+    fn handle_input_event(&mut self, ctx: &mut Context, key_input: InputAction) {
+        match key_input {
+            InputAction::Cancel => self.next = Some(Next::Back),
+            InputAction::Select => self.activate_selected(),
+            _ => {}
+        }
+        self.options.handle_input_event(ctx, key_input);
+    }
+
+    fn handle_mouse_event(&mut self, ctx: &mut Context, mouse_input: MouseAction) {
+        self.options.handle_mouse_event(ctx, mouse_input);
+        if let MouseAction::Click(point) = mouse_input {
+            if self.options.items[self.options.selected()].contains(point) {
+                self.activate_selected();
+            }
+        }
+    }
+
+    fn next_scene(&mut self, ctx: &mut Context) -> Option<Box<dyn Scene>> {
+        let make = match self.next.take() {
+            Some(Next::Retry) => self.retry.as_ref()?,
+            Some(Next::Back) => &self.back,
+            // None of the items have pages of their own
+            None => return self.options.next_scene(ctx),
+        };
+        match make(ctx) {
+            Ok(scene) => Some(scene),
+            // Still failing, say why and stay here
+            Err(e) => {
+                self.message_text = UIText::new(error_message(&e), Theme::fg_color(), 38.0, self.message_text.pos);
+                None
+            }
+        }
+    }
+}
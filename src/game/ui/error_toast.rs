@@ -0,0 +1,54 @@
+// A message along the bottom of the window for errors the player can carry on
+// from, like the clipboard being busy. Any scene can report one, the game
+// draws it over whatever scene is showing.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use ggez::{
+    glam::Vec2,
+    graphics::{Canvas, DrawMode, Mesh, Rect},
+    Context, GameError, GameResult,
+};
+use lazy_static::lazy_static;
+
+use crate::game::{animation::DrawablePos, drawable::Drawable, resources::theme::Theme};
+
+use super::{error_message, uitext::UIText};
+
+const SHOWN_FOR: Duration = Duration::from_secs(4);
+
+lazy_static! {
+    // The message and when it was reported
+    static ref TOAST: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+}
+
+// Replaces the message showing, if there is one
+pub fn report(message: String) {
+    log::warn!("{}", message);
+    *TOAST.lock().unwrap() = Some((message, Instant::now()));
+}
+
+pub fn report_error(context: &str, e: &GameError) { report(format!("{}: {}", context, error_message(e))); }
+
+pub fn draw(ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+    let mut toast = TOAST.lock().unwrap();
+    let Some((message, shown)) = toast.as_ref() else { return Ok(()) };
+    if shown.elapsed() > SHOWN_FOR {
+        *toast = None;
+        return Ok(());
+    }
+
+    let (w, h) = ctx.gfx.drawable_size();
+    let mut text = UIText::new(message.clone(), Theme::error_color(), 38.0, DrawablePos { x: 90.0, y: 0.0 });
+    let t_sz = text.text.measure(ctx)?;
+    text.pos.y = h - t_sz.y - 60.0;
+    let background =
+        Mesh::new_rectangle(ctx, DrawMode::fill(), Rect { x: 0.0, y: text.pos.y - 30.0, w, h: t_sz.y + 60.0 }, Theme::bg_color())?;
+    let border = Mesh::new_rectangle(ctx, DrawMode::fill(), Rect { x: 0.0, y: text.pos.y - 30.0, w, h: 4.0 }, Theme::error_color())?;
+    canvas.draw(&background, Vec2::new(0.0, 0.0));
+    canvas.draw(&border, Vec2::new(0.0, 0.0));
+    text.draw(ctx, canvas)
+}
@@ -1 +1,21 @@
+use ggez::GameError;
+
+pub mod clipboard;
+pub mod error_scene;
+pub mod error_toast;
 pub mod uitext;
+
+// GameError's Display names the variant, this is just the message for showing
+// on screen
+pub fn error_message(e: &GameError) -> String {
+    match e {
+        GameError::FilesystemError(msg)
+        | GameError::ConfigError(msg)
+        | GameError::ResourceLoadError(msg)
+        | GameError::AudioError(msg)
+        | GameError::CustomError(msg) => msg.clone(),
+        GameError::ResourceNotFound(msg, _) => format!("Couldn't find {}", msg),
+        GameError::IOError(e) => e.to_string(),
+        e => e.to_string(),
+    }
+}
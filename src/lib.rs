@@ -0,0 +1,5 @@
+// The menu constructors take positions and sizes as plain arguments, and
+// input handling uses guarded match arms on purpose.
+#![allow(clippy::too_many_arguments, clippy::type_complexity, clippy::module_inception, clippy::collapsible_match)]
+
+pub mod game;
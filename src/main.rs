@@ -1,33 +1,43 @@
-use std::path::PathBuf;
-
+use clap::Parser;
 use ggez::{
     conf::{FullscreenType, WindowMode},
-    event,
     winit::dpi::LogicalSize,
     GameResult,
 };
+use slidingpuzzle::game::{self, player, resources::StartOptions};
+use std::path::PathBuf;
 
-mod game;
+mod cli;
 
 // Turn the input image into a 2d array,
 // expect it's a square, and then divide the image
 // into X by X tiles
 
 pub fn main() -> GameResult {
-    env_logger::init();
+    let args = cli::Cli::parse();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(&args.log_level)).init();
+
+    if let Some(save_dir) = &args.save_dir {
+        player::set_save_dir(save_dir.clone());
+    }
+    if let Some(command) = args.command {
+        std::process::exit(cli::run_command(command, args.profile.as_deref()));
+    }
 
     let mut winmode = ggez::conf::WindowMode::default();
 
-    if let Some("fullscreen") = std::env::args().nth(1).as_deref() {
+    if args.fullscreen {
         winmode = winmode.fullscreen_type(FullscreenType::Desktop);
     } else {
-        winmode = winmode.dimensions(1820.0, 1030.0);
+        winmode = winmode.dimensions(args.width, args.height);
     }
 
     let mut cb = ggez::ContextBuilder::new("SlidingPuzzle", "cdknight")
         .window_setup(ggez::conf::WindowSetup::default().title("Sliding Puzzle"))
         .window_mode(winmode);
-    if let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") {
+    if let Some(resources) = &args.resources {
+        cb = cb.add_resource_path(resources.clone());
+    } else if let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") {
         let mut path = PathBuf::from(manifest_dir);
         path.push("resources");
         println!("Adding path {:?}", path);
@@ -35,10 +45,10 @@ pub fn main() -> GameResult {
     }
     let (mut ctx, event_loop) = cb.build()?;
 
-    if let Some("fullscreen") = std::env::args().nth(1).as_deref() {
+    if args.fullscreen {
         ctx.gfx.set_mode(WindowMode {
             fullscreen_type: FullscreenType::Desktop,
-            logical_size: Some(LogicalSize::from([ctx.gfx.drawable_size().0, 1030.0])),
+            logical_size: Some(LogicalSize::from([ctx.gfx.drawable_size().0, args.height])),
             ..Default::default()
         })?;
     }
@@ -59,9 +69,9 @@ pub fn main() -> GameResult {
     // copied straight from https://github.com/ggez/ggez/blob/master/examples/files.rs
 
     // Drop the mutex by putting it in its own scope
-    let state = game::GameState::new(&mut ctx)?;
+    let state = game::GameState::new(&mut ctx, StartOptions { start_game: args.start_game(), theme_file: args.theme })?;
 
     // ctx.gfx.set_screen_coordinates(&mut ctx, Rect::new(0.0, 0.0, win_width, win_height))?
 
-    event::run(ctx, event_loop, state)
+    game::event_loop::run(ctx, event_loop, state)
 }